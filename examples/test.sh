@@ -55,3 +55,24 @@ cargo run -- \
 cargo run -- \
 	-b 'tests/test.1:144852532-144852632.bam' \
 	-1 '1:144852633_144852634del' -2 '1:144852606G>A'
+
+# batch, all vcf variant pairs within 60bp
+cargo run -- \
+	-b 'tests/test.1:144852532-144852632.bam' \
+	--vcf tests/test.vcf --pairing window --window 60
+
+# batch, anchor against all vcf variants
+cargo run -- \
+	-b 'tests/test.1:144852532-144852632.bam' --fmt vcf \
+	--vcf tests/test.vcf --pairing anchor --anchor '1:144852537T>C' --output-format tsv
+
+# haplotypes of more than two variants
+cargo run -- \
+	-b 'tests/triple-snp.1:144854047-144854049.bam' \
+	--haplotype '1:144854047C>T' '1:144854048C>G' '1:144854049C>G'
+
+# custom linkage thresholds
+cargo run -- \
+	-b 'tests/test.1:144852532-144852632.bam' \
+	-1 '1:144852545C>T' -2 '1:144852606G>A' \
+	--config examples/thresholds.toml --min-reads 5 --valid-freq 0.001
@@ -0,0 +1,193 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::variant::is_skipped_allele;
+use crate::{Error, Variant};
+
+/// Strategy picking variant pairs from a variant list.
+#[derive(Debug, PartialEq)]
+pub enum Pairing {
+    // All pairs with start positions at most this distance apart.
+    Window(u32),
+    // Variant at this index against all others.
+    Anchor(usize),
+    // Adjacent variants only.
+    Adjacent,
+}
+
+impl Pairing {
+    /// Index pairs of variants, only variants on the same contig are paired.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::{Pairing, Variant};
+    ///
+    /// let variants = vec![
+    ///     Variant::from_vcf("1:100A>G").unwrap(),
+    ///     Variant::from_vcf("1:300C>T").unwrap(),
+    ///     Variant::from_vcf("1:1000G>A").unwrap(),
+    ///     Variant::from_vcf("2:200T>C").unwrap(),
+    /// ];
+    /// assert_eq!(Pairing::Window(200).pairs(&variants), vec![(0, 1)]);
+    /// assert_eq!(Pairing::Anchor(1).pairs(&variants), vec![(1, 0), (1, 2)]);
+    /// assert_eq!(Pairing::Adjacent.pairs(&variants), vec![(0, 1), (1, 2)]);
+    /// ```
+    pub fn pairs(&self, variants: &[Variant]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        match *self {
+            Self::Window(window) => {
+                // Sorted by position, each variant is only compared with those following within window.
+                let mut order: Vec<usize> = (0..variants.len()).collect();
+                order.sort_by_key(|i| (variants[*i].contig(), *variants[*i].start()));
+                for (k, i) in order.iter().enumerate() {
+                    let v1 = &variants[*i];
+                    for j in order[k + 1..].iter() {
+                        let v2 = &variants[*j];
+                        if v1.contig() != v2.contig() || v2.start() - v1.start() > window {
+                            break;
+                        };
+                        pairs.push((usize::min(*i, *j), usize::max(*i, *j)));
+                    }
+                }
+                pairs.sort_unstable();
+            }
+            Self::Anchor(anchor) => {
+                if let Some(v1) = variants.get(anchor) {
+                    for (j, v2) in variants.iter().enumerate() {
+                        if j != anchor && v1.contig() == v2.contig() {
+                            pairs.push((anchor, j));
+                        };
+                    }
+                };
+            }
+            Self::Adjacent => {
+                for (i, w) in variants.windows(2).enumerate() {
+                    if w[0].contig() == w[1].contig() {
+                        pairs.push((i, i + 1));
+                    };
+                }
+            }
+        };
+        pairs
+    }
+}
+
+/// Variants of vcf data line with their vcf format strings, one for each alt allele.
+fn line_variants(line: &str) -> Result<Vec<(String, Variant)>, Error> {
+    let variants = Variant::from_vcf_line(line)?;
+    let fields: Vec<&str> = line.split('\t').collect();
+    let names = fields[4]
+        .split(',')
+        .filter(|v| !is_skipped_allele(v))
+        .map(|v| format!("{}:{}{}>{}", fields[0], fields[1], fields[3], v));
+    Ok(names.zip(variants).collect())
+}
+
+/// Read variants from vcf file, with their vcf format strings, e.g. `1:12345A>G`. Multi-allelic
+/// records give one variant for each alt allele.
+pub fn read_vcf<P: AsRef<Path>>(path: P) -> Result<Vec<(String, Variant)>, Error> {
+    scan_vcf(path, None)
+}
+
+/// Find variants of vcf records by `ID`, position as `1:12345`, or allele as `1:12345A>G`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use varlink::{find_vcf, Variant};
+///
+/// let found = find_vcf("tests/test.vcf", "1:144852545").unwrap();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].0, "1:144852545C>T");
+/// assert_eq!(found[0].1, Variant::from_vcf("1:144852545C>T").unwrap());
+/// ```
+pub fn find_vcf<P: AsRef<Path>>(path: P, query: &str) -> Result<Vec<(String, Variant)>, Error> {
+    scan_vcf(path, Some(query))
+}
+
+/// Variants of all vcf records, or of those matching query.
+fn scan_vcf<P: AsRef<Path>>(path: P, query: Option<&str>) -> Result<Vec<(String, Variant)>, Error> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut variants = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        };
+        let found = line_variants(&line)?;
+        match query {
+            None => variants.extend(found),
+            Some(query) => {
+                let fields: Vec<&str> = line.splitn(4, '\t').collect();
+                if fields[2].split(';').any(|v| v != "." && v == query)
+                    || format!("{}:{}", fields[0], fields[1]) == query
+                {
+                    variants.extend(found);
+                } else {
+                    variants.extend(found.into_iter().filter(|(name, _)| name == query));
+                }
+            }
+        };
+    }
+    Ok(variants)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_vcf() {
+        let variants = read_vcf("tests/test.vcf").unwrap();
+        assert_eq!(variants.len(), 5);
+        assert_eq!(variants[0].0, "1:144852532G>GCCC");
+        assert_eq!(
+            variants[4].1,
+            Variant::from_vcf("1:144852632TAA>T").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_find_vcf() {
+        let found = find_vcf("tests/test.vcf", "1:144852632TAA>T").unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(find_vcf("tests/test.vcf", "rs1").unwrap().is_empty());
+        assert_eq!(
+            line_variants("1\t100\trs1\tA\tC,*,G\t.\t.\t.").unwrap(),
+            vec![
+                (
+                    "1:100A>C".to_string(),
+                    Variant::from_vcf("1:100A>C").unwrap()
+                ),
+                (
+                    "1:100A>G".to_string(),
+                    Variant::from_vcf("1:100A>G").unwrap()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pairs_window() {
+        let variants: Vec<Variant> = read_vcf("tests/test.vcf")
+            .unwrap()
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(
+            Pairing::Window(13).pairs(&variants),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(Pairing::Window(200).pairs(&variants).len(), 10);
+        // Unsorted input.
+        let variants = vec![
+            Variant::from_vcf("1:300C>T").unwrap(),
+            Variant::from_vcf("2:200T>C").unwrap(),
+            Variant::from_vcf("1:100A>G").unwrap(),
+            Variant::from_vcf("2:250T>C").unwrap(),
+        ];
+        assert_eq!(Pairing::Window(200).pairs(&variants), vec![(0, 2), (1, 3)]);
+    }
+}
@@ -0,0 +1,202 @@
+use bam::{Header, Record, Region};
+
+use crate::validate::read_position;
+use crate::{
+    AlignmentReader, Allele, Error, Fragments, HaplotypeTable, Link, Reason, Reference,
+    ValidateOptions, Variant, VariantValidate,
+};
+
+/// Region covering all variants with flank, variants must be on the same contig.
+pub fn merge_region(header: &Header, variants: &[&Variant], flank: u32) -> Result<Region, Error> {
+    let contig = variants.first().map_or("", |v| v.contig());
+    if let Some(v) = variants.iter().find(|v| v.contig() != contig) {
+        return Err(Error::InconsistentContig(
+            contig.to_string(),
+            v.contig().to_string(),
+        ));
+    };
+    let ref_id = header
+        .reference_id(contig)
+        .ok_or_else(|| Error::UnknownContig(contig.to_string()))?;
+    Ok(Region::new(
+        ref_id,
+        variants
+            .iter()
+            .map(|v| *v.start())
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(flank),
+        variants.iter().map(|v| *v.end()).max().unwrap_or(0) + 1 + flank,
+    ))
+}
+
+/// Fragment on reverse strand, by strand of its first read.
+fn is_reverse_fragment(record: &Record) -> bool {
+    let flag = record.flag();
+    if flag.is_paired() && flag.last_in_pair() {
+        flag.mate_is_reverse_strand()
+    } else {
+        flag.is_reverse_strand()
+    }
+}
+
+/// Count reads, or fragments if `fragment`, by their alleles of two variants, and reads by reason
+/// and by position of variant in alt reads, `reference` is needed by soft clip evidence.
+pub fn count_link(
+    reader: &mut AlignmentReader,
+    first: &Variant,
+    second: &Variant,
+    options: &ValidateOptions,
+    mut reference: Option<&mut Reference>,
+    fragment: bool,
+) -> Result<Link, Error> {
+    let region = merge_region(reader.header(), &[first, second], options.flank())?;
+    let core = merge_region(reader.header(), &[first, second], 0)?;
+    let mut link = Link::default();
+    let mut fragments = Fragments::default();
+    for rec in reader.fetch(&region)? {
+        let rec = rec.map_err(Error::Bam)?;
+        let r1 = rec.reason_with_reference(first, options, reference.as_deref_mut())?;
+        let r2 = rec.reason_with_reference(second, options, reference.as_deref_mut())?;
+        // Read fetched only by flank, not counted as not covering variants.
+        let in_core =
+            (rec.start() as u32) < core.end() && rec.calculate_end() as u32 > core.start();
+        for (i, (reason, variant)) in [(r1, first), (r2, second)].iter().enumerate() {
+            if in_core || *reason != Reason::NotCovered {
+                link.reasons[i].add(*reason);
+            };
+            // Only reads showing variant in their alignment, not by split, pair or soft clip.
+            if *reason == Reason::Supported {
+                if let Some(v) = read_position(&rec, variant) {
+                    link.positions[i].push(v);
+                };
+            };
+        }
+        let (f1, f2) = (r1.allele(), r2.allele());
+        if fragment {
+            fragments.push(rec.name(), &[f1, f2], is_reverse_fragment(&rec));
+        } else if let (Some(f1), Some(f2)) = (f1, f2) {
+            link.add(f1, f2, rec.flag().is_reverse_strand());
+        };
+    }
+    if fragment {
+        link = Link {
+            reasons: link.reasons,
+            positions: link.positions,
+            ..fragments.link()
+        };
+    };
+    Ok(link)
+}
+
+/// Count reads, or fragments if `fragment`, by their alleles of variants, `reference` is needed by
+/// soft clip evidence.
+pub fn count_haplotypes(
+    reader: &mut AlignmentReader,
+    variants: &[&Variant],
+    options: &ValidateOptions,
+    mut reference: Option<&mut Reference>,
+    fragment: bool,
+) -> Result<HaplotypeTable, Error> {
+    let region = merge_region(reader.header(), variants, options.flank())?;
+    let mut table = HaplotypeTable::default();
+    let mut fragments = Fragments::default();
+    for rec in reader.fetch(&region)? {
+        let rec = rec.map_err(Error::Bam)?;
+        let calls = variants
+            .iter()
+            .map(|v| {
+                rec.reason_with_reference(v, options, reference.as_deref_mut())
+                    .map(|v| v.allele())
+            })
+            .collect::<Result<Vec<Option<Allele>>, Error>>()?;
+        if fragment {
+            fragments.push(rec.name(), &calls, is_reverse_fragment(&rec));
+        } else {
+            table.add(&calls);
+        };
+    }
+    if fragment {
+        table = fragments.haplotypes();
+    };
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_region() {
+        let reader =
+            AlignmentReader::from_path("tests/test.1:144852532-144852632.bam", None).unwrap();
+        let v1 = Variant::from_vcf("1:144852545C>T").unwrap();
+        let v2 = Variant::from_vcf("1:144852537T>C").unwrap();
+        let v3 = Variant::from_vcf("2:144852537T>C").unwrap();
+        let v4 = Variant::from_vcf("chrT:4TA>T").unwrap();
+        let region = merge_region(reader.header(), &[&v1, &v2], 0).unwrap();
+        assert_eq!((region.start(), region.end()), (144852537, 144852546));
+        assert!(matches!(
+            merge_region(reader.header(), &[&v1, &v3], 0),
+            Err(Error::InconsistentContig(..))
+        ));
+        assert!(matches!(
+            merge_region(reader.header(), &[&v4], 0),
+            Err(Error::UnknownContig(_))
+        ));
+    }
+
+    #[test]
+    fn test_count_link_positions() {
+        let mut reader =
+            AlignmentReader::from_path("tests/test.1:144852532-144852632.bam", None).unwrap();
+        let v1 = Variant::from_vcf("1:144852545C>T").unwrap();
+        let v2 = Variant::from_vcf("1:144852537T>C").unwrap();
+        let mut options = ValidateOptions::default();
+        let link = count_link(&mut reader, &v1, &v2, &options, None, false).unwrap();
+        assert_eq!(
+            link.positions[0].len() as u32,
+            link.reasons[0].count(Reason::Supported)
+        );
+        options.set_end_distance(5);
+        let near = count_link(&mut reader, &v1, &v2, &options, None, false).unwrap();
+        assert!(near.reasons[0].count(Reason::NearEnd) > 0);
+        assert!(near.first < link.first);
+    }
+
+    #[test]
+    fn test_count_link_flank() {
+        let mut reader =
+            AlignmentReader::from_path("tests/test.1:144852532-144852632.bam", None).unwrap();
+        let v1 = Variant::from_vcf("1:144852606G>A").unwrap();
+        let v2 = Variant::from_hgvs("1:144852633_144852634del").unwrap();
+        let mut options = ValidateOptions::default();
+        let link = count_link(&mut reader, &v1, &v2, &options, None, false).unwrap();
+        // Reads in flank fetched by realignment not counted as not covered.
+        options.set_realign(true);
+        let flank = count_link(&mut reader, &v1, &v2, &options, None, false).unwrap();
+        for i in 0..2 {
+            assert_eq!(
+                flank.reasons[i].count(Reason::NotCovered),
+                link.reasons[i].count(Reason::NotCovered)
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_position() {
+        let mut header = Header::new();
+        header.push_line("@SQ\tSN:chrT\tLN:60").unwrap();
+        let var = Variant::from_hgvs("chrT:13C>T").unwrap();
+        let mut rec = Record::new();
+        let line = "read\t0\tchrT\t3\t60\t2S18M\t*\t0\t0\tTTGTAAAAAGCTTACACAGT\t*\tMD:Z:10C7";
+        rec.fill_from_sam(line, &header).unwrap();
+        assert_eq!(read_position(&rec, &var), Some(13));
+        rec.fill_from_sam(&line.replacen("\t0\t", "\t16\t", 1), &header)
+            .unwrap();
+        assert_eq!(read_position(&rec, &var), Some(8));
+        // In soft clip.
+        let var = Variant::from_hgvs("chrT:2C>T").unwrap();
+        assert_eq!(read_position(&rec, &var), None);
+    }
+}
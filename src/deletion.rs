@@ -0,0 +1,52 @@
+use bam::record::Record;
+
+use crate::split::{split_alignments, SPLIT_TOLERANCE};
+use crate::Variant;
+
+/// Record and one of its split alignments on the same strand flank deletion, one ending before
+/// it and the other starting after it.
+pub(crate) fn is_split(record: &Record, variant: &Variant, min_mapq: u8) -> bool {
+    let (start, end) = (record.start() as u32 + 1, record.calculate_end() as u32);
+    let reverse = record.flag().is_reverse_strand();
+    let before = |end: u32| (end + 1).abs_diff(*variant.start()) <= SPLIT_TOLERANCE;
+    let after = |start: u32| start.abs_diff(variant.end() + 1) <= SPLIT_TOLERANCE;
+    split_alignments(record).iter().any(|v| {
+        v.contig == variant.contig()
+            && v.reverse == reverse
+            && v.mapq >= min_mapq
+            && ((before(end) && after(v.start)) || (before(v.end) && after(start)))
+    })
+}
+
+/// Record and its mate in forward-reverse orientation flank deletion, with insert longer than
+/// `max_insert` but not once the deleted interval is taken out. Mate is assumed as long as record.
+pub(crate) fn is_discordant(record: &Record, variant: &Variant, max_insert: u32) -> bool {
+    let flag = record.flag();
+    if max_insert == 0
+        || !flag.is_paired()
+        || !flag.mate_is_mapped()
+        || record.mate_ref_id() != record.ref_id()
+    {
+        return false;
+    };
+    let insert = record.template_len().unsigned_abs();
+    let del_len = variant.affected_length();
+    if insert <= max_insert || insert.saturating_sub(del_len) > max_insert {
+        return false;
+    };
+    let (start, end) = (record.start() as u32 + 1, record.calculate_end() as u32);
+    let mate_start = record.mate_start() as u32 + 1;
+    if start <= mate_start {
+        // Forward record before deletion, reverse mate after it.
+        !flag.is_reverse_strand()
+            && flag.mate_is_reverse_strand()
+            && end < *variant.start()
+            && mate_start > *variant.end()
+    } else {
+        // Reverse record after deletion, forward mate before it.
+        flag.is_reverse_strand()
+            && !flag.mate_is_reverse_strand()
+            && start > *variant.end()
+            && mate_start + record.query_len() <= *variant.start()
+    }
+}
@@ -0,0 +1,136 @@
+use std::error;
+use std::fmt::{self, Display};
+use std::io;
+use std::path::PathBuf;
+
+/// Errors of varlink.
+#[derive(Debug)]
+pub enum Error {
+    // Malformed input, with byte offset where parsing failed.
+    Parse {
+        input: String,
+        offset: usize,
+    },
+    // Contig not found in alignment header or reference.
+    UnknownContig(String),
+    // Variants counted together are on different contigs.
+    InconsistentContig(String, String),
+    // Interval out of reference contig, 1-based and closed.
+    OutOfRange {
+        contig: String,
+        start: u32,
+        end: u32,
+    },
+    // Variant reference sequence differs from reference.
+    RefMismatch {
+        contig: String,
+        start: u32,
+        expected: String,
+    },
+    // Read without `MD` tag, needed to compare read with reference.
+    MissingMd(String),
+    // Reference fasta needed but not given, e.g. for duplication without sequence.
+    ReferenceRequired(String),
+    // Transcript not found in annotation.
+    UnknownTranscript(String),
+    // Transcript annotation needed but not given, for transcript hgvs.
+    AnnotationRequired(String),
+    // No single vcf allele found by ID, position or allele.
+    RecordNotFound(String),
+    // Index of alignment file or reference not found.
+    IndexMissing(PathBuf),
+    // External program not found in `PATH`, e.g. samtools for cram.
+    ProgramMissing(String),
+    // Reading or decoding alignment file failed.
+    Bam(io::Error),
+    // Other I/O failure.
+    Io(io::Error),
+}
+
+impl Error {
+    /// Parse error of nom parser, offset is where the remaining input starts.
+    pub(crate) fn parse(input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        let offset = match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => input.len() - e.input.len(),
+            nom::Err::Incomplete(_) => input.len(),
+        };
+        Self::Parse {
+            input: input.to_string(),
+            offset,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { input, offset } => {
+                write!(f, "Invalid input at offset {}: {}", offset, input)
+            }
+            Self::UnknownContig(v) => write!(f, "No such contig found: {}.", v),
+            Self::InconsistentContig(v1, v2) => {
+                write!(f, "Inconsistent contig: {} and {}.", v1, v2)
+            }
+            Self::OutOfRange { contig, start, end } => {
+                write!(
+                    f,
+                    "Interval out of reference: {}:{}-{}.",
+                    contig, start, end
+                )
+            }
+            Self::RefMismatch {
+                contig,
+                start,
+                expected,
+            } => write!(
+                f,
+                "Reference mismatch at {}:{}, {} in reference.",
+                contig, start, expected
+            ),
+            Self::MissingMd(v) => write!(f, "No MD tag found in read: {}.", v),
+            Self::ReferenceRequired(v) => write!(f, "Reference fasta is required for {}.", v),
+            Self::UnknownTranscript(v) => write!(f, "No such transcript found: {}.", v),
+            Self::AnnotationRequired(v) => {
+                write!(f, "Transcript annotation is required for {}.", v)
+            }
+            Self::RecordNotFound(v) => write!(f, "No single vcf allele found for: {}.", v),
+            Self::IndexMissing(v) => write!(f, "No index found for: {}.", v.display()),
+            Self::ProgramMissing(v) => write!(f, "No {} found in PATH.", v),
+            Self::Bam(e) => write!(f, "Alignment file error: {}", e),
+            Self::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Bam(e) | Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        let input = "1:12345A>";
+        let err = nom::Err::Error(nom::error::Error::new(
+            &input[9..],
+            nom::error::ErrorKind::NonEmpty,
+        ));
+        assert!(matches!(
+            Error::parse(input, err),
+            Error::Parse { offset: 9, .. }
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::{Allele, HaplotypeTable, Link};
+
+/// Merge support of one more read into the fragment support of a variant.
+///
+/// Return `Err(())` if the read disagrees with the support already seen.
+fn merge_support(curr: Option<Allele>, next: Option<Allele>) -> Result<Option<Allele>, ()> {
+    match (curr, next) {
+        (Some(a), Some(b)) if a != b => Err(()),
+        (Some(a), _) => Ok(Some(a)),
+        (None, b) => Ok(b),
+    }
+}
+
+/// Variant alleles of a fragment, merged from all reads sharing the same name.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Fragment {
+    calls: Vec<Option<Allele>>,
+    conflict: bool,
+    // Fragment on reverse strand, by its first read.
+    reverse: bool,
+}
+
+impl Fragment {
+    /// Merge validation results of a read into the fragment, one for each variant.
+    pub fn merge(&mut self, calls: &[Option<Allele>]) {
+        if self.conflict {
+            return;
+        };
+        if self.calls.len() < calls.len() {
+            self.calls.resize(calls.len(), None);
+        };
+        let merged: Result<Vec<Option<Allele>>, ()> = self
+            .calls
+            .iter()
+            .zip(calls.iter())
+            .map(|(curr, next)| merge_support(*curr, *next))
+            .collect();
+        match merged {
+            Ok(v) => self.calls = v,
+            Err(_) => self.conflict = true,
+        }
+    }
+
+    /// Fragment on reverse strand, by its first read.
+    pub fn is_reverse(&self) -> bool {
+        self.reverse
+    }
+
+    /// Mates disagree on at least one variant.
+    pub fn is_conflict(&self) -> bool {
+        self.conflict
+    }
+
+    /// Merged support for each variant.
+    pub fn calls(&self) -> &[Option<Allele>] {
+        &self.calls
+    }
+
+    /// Alleles of first and second variant, `None` if conflict or any is not covered.
+    pub fn support(&self) -> Option<(Allele, Allele)> {
+        if self.conflict {
+            return None;
+        };
+        match self.calls[..] {
+            [Some(f1), Some(f2)] => Some((f1, f2)),
+            _ => None,
+        }
+    }
+}
+
+/// Fragments grouped by query name.
+#[derive(Debug, Default)]
+pub struct Fragments {
+    inner: HashMap<Vec<u8>, Fragment>,
+}
+
+impl Fragments {
+    /// Add validation results of a read to the fragment it belongs to, with the fragment strand.
+    pub fn push(&mut self, name: &[u8], calls: &[Option<Allele>], reverse: bool) {
+        if let Some(v) = self.inner.get_mut(name) {
+            v.merge(calls);
+        } else {
+            let mut fragment = Fragment {
+                reverse,
+                ..Default::default()
+            };
+            fragment.merge(calls);
+            self.inner.insert(name.to_vec(), fragment);
+        };
+    }
+
+    /// Count fragments into link of two variants.
+    pub fn link(&self) -> Link {
+        let mut link = Link::default();
+        for fragment in self.inner.values() {
+            if fragment.is_conflict() {
+                link.conflict += 1;
+            } else if let Some((f1, f2)) = fragment.support() {
+                link.add(f1, f2, fragment.is_reverse());
+            };
+        }
+        link
+    }
+
+    /// Count fragments into haplotype table of any number of variants.
+    pub fn haplotypes(&self) -> HaplotypeTable {
+        let mut table = HaplotypeTable::default();
+        for fragment in self.inner.values() {
+            if fragment.is_conflict() {
+                table.conflict += 1;
+            } else {
+                table.add(fragment.calls());
+            };
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Allele::{Alt, Other, Ref};
+
+    #[test]
+    fn test_merge_mates() {
+        // Read1 covers first variant, read2 covers second.
+        let mut fragment = Fragment::default();
+        fragment.merge(&[Some(Alt), None]);
+        assert_eq!(fragment.support(), None);
+        fragment.merge(&[None, Some(Alt)]);
+        assert_eq!(fragment.support(), Some((Alt, Alt)));
+        // Overlapping mates agree.
+        fragment.merge(&[Some(Alt), Some(Alt)]);
+        assert_eq!(fragment.support(), Some((Alt, Alt)));
+        assert!(!fragment.is_conflict());
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let mut fragment = Fragment::default();
+        fragment.merge(&[Some(Alt), Some(Ref)]);
+        fragment.merge(&[Some(Ref), Some(Ref)]);
+        assert!(fragment.is_conflict());
+        assert_eq!(fragment.support(), None);
+    }
+
+    #[test]
+    fn test_fragments_link() {
+        let mut fragments = Fragments::default();
+        fragments.push(b"r1", &[Some(Alt), None], false);
+        fragments.push(b"r1", &[None, Some(Alt)], false);
+        fragments.push(b"r2", &[Some(Alt), Some(Ref)], true);
+        fragments.push(b"r2", &[Some(Alt), Some(Ref)], true);
+        fragments.push(b"r3", &[Some(Ref), Some(Alt)], false);
+        fragments.push(b"r3", &[Some(Alt), Some(Alt)], false);
+        fragments.push(b"r4", &[Some(Ref), None], false);
+        fragments.push(b"r5", &[Some(Other), Some(Ref)], true);
+        let link = fragments.link();
+        assert_eq!(
+            (
+                link.both,
+                link.first,
+                link.second,
+                link.neither,
+                link.other_ref,
+                link.conflict,
+                link.reverse.first
+            ),
+            (1, 1, 0, 0, 1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_fragments_haplotypes() {
+        let mut fragments = Fragments::default();
+        fragments.push(b"r1", &[Some(Alt), None, None], false);
+        fragments.push(b"r1", &[None, Some(Ref), Some(Alt)], false);
+        fragments.push(b"r2", &[Some(Alt), Some(Ref), None], false);
+        let table = fragments.haplotypes();
+        assert_eq!(table.count(&[Alt, Ref, Alt]), 1);
+        assert_eq!(table.partial, 1);
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+
+use crate::Allele;
+
+/// Read or fragment counts of allele combinations observed over any number of variants.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct HaplotypeTable {
+    // Counts of fully informative reads by allele combination.
+    #[serde(rename = "haplotypes", serialize_with = "serialize_counts")]
+    pub counts: BTreeMap<Vec<Allele>, u32>,
+    // Reads covering some but not all variants.
+    pub partial: u32,
+    // Fragments whose mates disagree on any variant.
+    pub conflict: u32,
+}
+
+/// Allele combination in format like `1-0-x`, with `x` for other allele.
+fn haplotype_string(alleles: &[Allele]) -> String {
+    alleles
+        .iter()
+        .map(|v| match v {
+            Allele::Ref => "0",
+            Allele::Alt => "1",
+            Allele::Other => "x",
+        })
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Serialize counts keyed by allele combination string, ordered alt, ref, then other for each
+/// variant.
+fn serialize_counts<S: Serializer>(
+    counts: &BTreeMap<Vec<Allele>, u32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let rank = |v: &Allele| match v {
+        Allele::Alt => 0,
+        Allele::Ref => 1,
+        Allele::Other => 2,
+    };
+    let mut entries = counts.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(k, _)| k.iter().map(rank).collect::<Vec<u8>>());
+    serializer.collect_map(entries.into_iter().map(|(k, v)| (haplotype_string(k), v)))
+}
+
+impl HaplotypeTable {
+    /// Count a read or fragment by its allele of each variant.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::Allele::{Alt, Other, Ref};
+    /// use varlink::HaplotypeTable;
+    ///
+    /// let mut table = HaplotypeTable::default();
+    /// table.add(&[Some(Alt), Some(Ref), Some(Alt)]);
+    /// table.add(&[Some(Alt), Some(Ref), Some(Alt)]);
+    /// table.add(&[Some(Alt), Some(Other), Some(Alt)]);
+    /// table.add(&[Some(Alt), None, Some(Alt)]);
+    /// table.add(&[None, None, None]);
+    /// assert_eq!(table.count(&[Alt, Ref, Alt]), 2);
+    /// assert_eq!(table.count(&[Alt, Other, Alt]), 1);
+    /// assert_eq!(table.count(&[Alt, Alt, Alt]), 0);
+    /// assert_eq!(table.partial, 1);
+    /// ```
+    pub fn add(&mut self, calls: &[Option<Allele>]) {
+        let alleles: Option<Vec<Allele>> = calls.iter().copied().collect();
+        if let Some(v) = alleles {
+            *self.counts.entry(v).or_insert(0) += 1;
+        } else if calls.iter().any(|v| v.is_some()) {
+            self.partial += 1;
+        };
+    }
+
+    /// Read count of an allele combination.
+    pub fn count(&self, alleles: &[Allele]) -> u32 {
+        self.counts.get(alleles).copied().unwrap_or(0)
+    }
+
+    /// Read count of all fully informative reads.
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Allele::{Alt, Other, Ref};
+
+    #[test]
+    fn test_haplotype_string() {
+        assert_eq!(haplotype_string(&[Alt, Ref, Other]), "1-0-x");
+        assert_eq!(haplotype_string(&[]), "");
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut table = HaplotypeTable::default();
+        table.add(&[Some(Alt), Some(Alt)]);
+        table.add(&[Some(Ref), Some(Alt)]);
+        assert_eq!(
+            serde_json::to_string(&table).unwrap(),
+            r#"{"haplotypes":{"1-1":1,"0-1":1},"partial":0,"conflict":0}"#
+        );
+        // Keys ordered alt, ref, then other.
+        table.add(&[Some(Other), Some(Ref)]);
+        table.add(&[Some(Alt), Some(Other)]);
+        table.add(&[Some(Ref), Some(Ref)]);
+        assert_eq!(
+            serde_json::to_string(&table).unwrap(),
+            r#"{"haplotypes":{"1-1":1,"1-x":1,"0-1":1,"0-0":1,"x-0":1},"partial":0,"conflict":0}"#
+        );
+    }
+}
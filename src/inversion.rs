@@ -0,0 +1,98 @@
+use bam::record::Record;
+
+use crate::split::{split_alignments, SPLIT_TOLERANCE};
+use crate::{Reason, Variant};
+
+/// Reverse complement of nucleotide sequence, other bases are kept.
+pub(crate) fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|v| match v.to_ascii_uppercase() {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'T' => b'A',
+            v => v,
+        })
+        .collect()
+}
+
+/// Span `start..=end` lies inside inversion, within tolerance.
+fn is_inside(start: u32, end: u32, variant: &Variant) -> bool {
+    start + SPLIT_TOLERANCE >= *variant.start() && end <= variant.end() + SPLIT_TOLERANCE
+}
+
+/// Span `start..=end` ends next to either inversion breakpoint, within tolerance.
+fn is_adjacent(start: u32, end: u32, variant: &Variant) -> bool {
+    (end + 1).abs_diff(*variant.start()) <= SPLIT_TOLERANCE
+        || start.abs_diff(variant.end() + 1) <= SPLIT_TOLERANCE
+}
+
+/// Record and one of its split alignments on the opposite strand, one inside inversion and the
+/// other next to its breakpoint.
+pub(crate) fn is_split(record: &Record, variant: &Variant) -> bool {
+    let (start, end) = (record.start() as u32 + 1, record.calculate_end() as u32);
+    let reverse = record.flag().is_reverse_strand();
+    split_alignments(record).iter().any(|v| {
+        v.contig == variant.contig()
+            && v.reverse != reverse
+            && ((is_inside(start, end, variant) && is_adjacent(v.start, v.end, variant))
+                || (is_inside(v.start, v.end, variant) && is_adjacent(start, end, variant)))
+    })
+}
+
+/// Validate record aligned through inversion, by comparing read bases with reference segment and
+/// its reverse complement.
+pub(crate) fn validate(record: &Record, variant: &Variant, min_base_qual: u8) -> Reason {
+    let (first, last) = (variant.start() - 1, variant.end() - 1);
+    let len = variant.affected_length() as usize;
+    let quals = record.qualities();
+    let quals = if quals.available() { quals.raw() } else { &[] };
+    let mut read = Vec::new();
+    let mut refseq = Vec::new();
+    let entries = match record.alignment_entries() {
+        Ok(v) => v,
+        Err(_) => return Reason::NotCovered,
+    };
+    for entry in entries {
+        match (entry.ref_pos(), entry.record_pos()) {
+            (Some(pos), _) if pos < first => continue,
+            (Some(pos), _) if pos > last => break,
+            (Some(_), Some(i)) => {
+                let low = quals.get(i as usize).is_some_and(|v| *v < min_base_qual);
+                match (entry.record_nt(), entry.ref_nt()) {
+                    (Some(nt), Some(ref_nt)) => {
+                        read.push(if low { b'N' } else { nt });
+                        refseq.push(ref_nt);
+                    }
+                    _ => return Reason::NotCovered,
+                };
+            }
+            // Insertion before segment.
+            (None, _) if refseq.is_empty() => continue,
+            // Insertion after segment.
+            (None, _) if refseq.len() == len => break,
+            // Deletion or insertion inside segment.
+            _ => return Reason::OtherIndel,
+        };
+    }
+    if refseq.len() != len {
+        return Reason::TooShort;
+    };
+    let matches = |seq: &[u8]| read.iter().zip(seq).all(|(a, b)| *a == b'N' || a == b);
+    match (matches(&reverse_complement(&refseq)), matches(&refseq)) {
+        (true, false) => Reason::Supported,
+        (false, true) => Reason::Reference,
+        _ => Reason::Ambiguous,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_complement() {
+        assert_eq!(reverse_complement(b"AACGTN"), b"NACGTT");
+    }
+}
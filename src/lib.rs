@@ -1,11 +1,39 @@
 extern crate bam;
 extern crate nom;
+extern crate serde;
+extern crate serde_json;
+extern crate toml;
 
+mod batch;
+mod count;
+mod deletion;
+mod error;
+mod fragment;
+mod haplotype;
+mod inversion;
 mod link;
+mod longread;
+mod reader;
+mod realign;
+mod reason;
+mod reference;
+mod softclip;
+mod split;
+pub mod stats;
+mod transcript;
 mod validate;
 pub mod variant;
 
-pub use link::{Link, Linkage};
-pub use validate::{ValidateOptions, VariantValidate};
+pub use batch::{find_vcf, read_vcf, Pairing};
+pub use count::{count_haplotypes, count_link, merge_region};
+pub use error::Error;
+pub use fragment::{Fragment, Fragments};
+pub use haplotype::HaplotypeTable;
+pub use link::{Link, LinkStats, Linkage, LinkageThresholds, StrandBias, StrandCounts};
+pub use reader::{AlignmentReader, CramReader, Records, SamRecords, StreamReader};
+pub use reason::{Reason, Reasons};
+pub use reference::Reference;
+pub use transcript::Transcripts;
+pub use validate::{parse_flags, Allele, ValidateOptions, VariantValidate};
 pub use variant::Format as VarFormat;
-pub use variant::{Edit, Variant};
+pub use variant::{Edit, Normalization, Variant};
@@ -1,10 +1,94 @@
 use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
 
-const VALID_READ: u32 = 3;
-const VALID_FREQ: f32 = 0.01;
-const CONF_FREQ: f32 = 0.96;
+use serde::{Deserialize, Serialize, Serializer};
 
-#[derive(Debug, PartialEq)]
+use crate::stats::{fisher_exact, median, strand_odds_ratio};
+use crate::{Allele, Error, Reasons};
+
+/// Cutoffs used to infer linkage from read counts.
+///
+/// A read count is considered valid if it reaches the minimum read count of the linkage class
+/// being tested, and absent if it is below both that count and `valid_freq`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkageThresholds {
+    // Min read count for cis.
+    pub cis_reads: u32,
+    // Min read count for trans.
+    pub trans_reads: u32,
+    // Min read count for super.
+    pub super_reads: u32,
+    // Min read count for sub.
+    pub sub_reads: u32,
+    // Min read count for cross.
+    pub cross_reads: u32,
+    // Frequency under which read count is considered noise.
+    #[serde(serialize_with = "serialize_f32")]
+    pub valid_freq: f32,
+    // Frequency from which read count is considered confident.
+    #[serde(serialize_with = "serialize_f32")]
+    pub conf_freq: f32,
+    // Min read count on each strand of reads supporting the linkage, 0 to not check.
+    pub strand_reads: u32,
+}
+
+/// Serialize f32 by its shortest decimal, as `0.01` rather than `0.009999999776482582`.
+fn serialize_f32<S: Serializer>(v: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(v.to_string().parse().unwrap_or_else(|_| f64::from(*v)))
+}
+
+impl Default for LinkageThresholds {
+    fn default() -> Self {
+        Self {
+            cis_reads: 3,
+            trans_reads: 3,
+            super_reads: 3,
+            sub_reads: 3,
+            cross_reads: 3,
+            valid_freq: 0.01,
+            conf_freq: 0.96,
+            strand_reads: 0,
+        }
+    }
+}
+
+impl LinkageThresholds {
+    /// Set min read count of all linkage classes.
+    pub fn set_min_reads(&mut self, reads: u32) {
+        self.cis_reads = reads;
+        self.trans_reads = reads;
+        self.super_reads = reads;
+        self.sub_reads = reads;
+        self.cross_reads = reads;
+    }
+
+    /// Load thresholds from toml file, missing keys take default values.
+    ///
+    /// ```toml
+    /// cis_reads = 2
+    /// trans_reads = 2
+    /// valid_freq = 0.001
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse thresholds from toml, error input is the failing line and offset is its column.
+    fn parse(content: &str) -> Result<Self, Error> {
+        toml::from_str(content).map_err(|e| {
+            let (line, col) = e.line_col().unwrap_or((0, 0));
+            Error::Parse {
+                input: content.lines().nth(line).unwrap_or("").to_string(),
+                offset: col,
+            }
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Linkage {
     Cis,
     Trans,
@@ -25,15 +109,137 @@ impl Display for Linkage {
     }
 }
 
-#[derive(Debug, Default)]
+/// Read counts of one strand by allele of first and second variant, alt or ref.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct StrandCounts {
+    pub both: u32,
+    pub first: u32,
+    pub second: u32,
+    pub neither: u32,
+}
+
+/// Strand bias of alt reads against other reads.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StrandBias {
+    // Two-sided Fisher exact test p-value.
+    pub p_value: f64,
+    // Strand odds ratio, as GATK `SOR`.
+    pub sor: f64,
+}
+
+impl StrandBias {
+    fn new(alt_fwd: u32, alt_rev: u32, other_fwd: u32, other_rev: u32) -> Self {
+        Self {
+            p_value: fisher_exact(other_fwd, other_rev, alt_fwd, alt_rev),
+            sor: strand_odds_ratio(other_fwd, other_rev, alt_fwd, alt_rev),
+        }
+    }
+}
+
+/// Read counts by allele of first and second variant, a 3×3 table of alt, ref and other.
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct Link {
+    // Alt of both.
     pub both: u32,
+    // Alt of first, ref of second.
     pub first: u32,
+    // Ref of first, alt of second.
     pub second: u32,
+    // Ref of both.
     pub neither: u32,
+    // Alt of first, other allele at second.
+    pub alt_other: u32,
+    // Ref of first, other allele at second.
+    pub ref_other: u32,
+    // Other allele at first, alt of second.
+    pub other_alt: u32,
+    // Other allele at first, ref of second.
+    pub other_ref: u32,
+    // Other alleles at both.
+    pub other_other: u32,
+    // Fragments whose mates disagree on any variant.
+    pub conflict: u32,
+    // Alt and ref counts of forward strand reads, or fragments by first read.
+    pub forward: StrandCounts,
+    // Alt and ref counts of reverse strand reads, or fragments by first read.
+    pub reverse: StrandCounts,
+    // Read counts by reason of first and second variant allele.
+    pub reasons: [Reasons; 2],
+    // Positions in read of first and second variant in reads supporting it, from read 5' end.
+    #[serde(skip)]
+    pub positions: [Vec<u32>; 2],
 }
 
 impl Link {
+    /// Count a read or fragment by its alleles of first and second variant, and its strand.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::{Allele, Link};
+    ///
+    /// let mut link = Link::default();
+    /// link.add(Allele::Alt, Allele::Alt, false);
+    /// link.add(Allele::Ref, Allele::Other, true);
+    /// assert_eq!(link.both, 1);
+    /// assert_eq!(link.forward.both, 1);
+    /// assert_eq!(link.ref_other, 1);
+    /// assert_eq!(link.table(), [[1, 0, 0], [0, 0, 1], [0, 0, 0]]);
+    /// ```
+    pub fn add(&mut self, first: Allele, second: Allele, reverse: bool) {
+        *self.cell(first, second) += 1;
+        let strand = if reverse {
+            &mut self.reverse
+        } else {
+            &mut self.forward
+        };
+        match (first, second) {
+            (Allele::Alt, Allele::Alt) => strand.both += 1,
+            (Allele::Alt, Allele::Ref) => strand.first += 1,
+            (Allele::Ref, Allele::Alt) => strand.second += 1,
+            (Allele::Ref, Allele::Ref) => strand.neither += 1,
+            _ => {}
+        };
+    }
+
+    fn cell(&mut self, first: Allele, second: Allele) -> &mut u32 {
+        match (first, second) {
+            (Allele::Alt, Allele::Alt) => &mut self.both,
+            (Allele::Alt, Allele::Ref) => &mut self.first,
+            (Allele::Ref, Allele::Alt) => &mut self.second,
+            (Allele::Ref, Allele::Ref) => &mut self.neither,
+            (Allele::Alt, Allele::Other) => &mut self.alt_other,
+            (Allele::Ref, Allele::Other) => &mut self.ref_other,
+            (Allele::Other, Allele::Alt) => &mut self.other_alt,
+            (Allele::Other, Allele::Ref) => &mut self.other_ref,
+            (Allele::Other, Allele::Other) => &mut self.other_other,
+        }
+    }
+
+    /// Read count of an allele combination of first and second variant.
+    pub fn count(&self, first: Allele, second: Allele) -> u32 {
+        let index = |v| match v {
+            Allele::Alt => 0,
+            Allele::Ref => 1,
+            Allele::Other => 2,
+        };
+        self.table()[index(first)][index(second)]
+    }
+
+    /// Read counts as rows of first variant allele and columns of second, ordered alt, ref, other.
+    pub fn table(&self) -> [[u32; 3]; 3] {
+        [
+            [self.both, self.first, self.alt_other],
+            [self.second, self.neither, self.ref_other],
+            [self.other_alt, self.other_ref, self.other_other],
+        ]
+    }
+
+    /// Read count with any other allele, excluded from linkage and LD statistics.
+    pub fn other(&self) -> u32 {
+        self.alt_other + self.ref_other + self.other_alt + self.other_ref + self.other_other
+    }
+
     #[inline]
     fn either(&self) -> u32 {
         // Read count that support only first or second.
@@ -86,62 +292,214 @@ impl Link {
         (self.second as f32) / (self.any() as f32)
     }
 
+    /// Haplotype frequency of both variants, allele frequency of first and second, among all reads.
+    fn freqs(&self) -> Option<(f64, f64, f64)> {
+        let total = f64::from(self.any() + self.neither);
+        if total == 0.0 {
+            return None;
+        };
+        Some((
+            f64::from(self.both) / total,
+            f64::from(self.both + self.first) / total,
+            f64::from(self.both + self.second) / total,
+        ))
+    }
+
+    /// Linkage disequilibrium coefficient D.
+    pub fn d(&self) -> Option<f64> {
+        let (p_ab, p_a, p_b) = self.freqs()?;
+        Some(p_ab - p_a * p_b)
+    }
+
+    /// Normalized D, D / Dmax, negative when variants tend to be on different haplotypes.
+    pub fn d_prime(&self) -> Option<f64> {
+        let (_, p_a, p_b) = self.freqs()?;
+        let d = self.d()?;
+        let dmax = if d >= 0.0 {
+            f64::min(p_a * (1.0 - p_b), (1.0 - p_a) * p_b)
+        } else {
+            f64::min(p_a * p_b, (1.0 - p_a) * (1.0 - p_b))
+        };
+        if dmax > 0.0 {
+            Some(d / dmax)
+        } else {
+            None
+        }
+    }
+
+    /// Squared correlation coefficient r² between the two variants.
+    pub fn r2(&self) -> Option<f64> {
+        let (_, p_a, p_b) = self.freqs()?;
+        let d = self.d()?;
+        let denom = p_a * (1.0 - p_a) * p_b * (1.0 - p_b);
+        if denom > 0.0 {
+            Some(d * d / denom)
+        } else {
+            None
+        }
+    }
+
+    /// Odds ratio of the 2×2 table, with 0.5 added to each cell if any is zero.
+    pub fn odds_ratio(&self) -> Option<f64> {
+        if self.any() + self.neither == 0 {
+            return None;
+        };
+        let correction =
+            if self.both == 0 || self.first == 0 || self.second == 0 || self.neither == 0 {
+                0.5
+            } else {
+                0.0
+            };
+        Some(
+            (f64::from(self.both) + correction) * (f64::from(self.neither) + correction)
+                / ((f64::from(self.first) + correction) * (f64::from(self.second) + correction)),
+        )
+    }
+
+    /// Two-sided Fisher exact test p-value for association of the two variants.
+    pub fn p_value(&self) -> f64 {
+        fisher_exact(self.both, self.first, self.second, self.neither)
+    }
+
+    /// Strand bias of first variant alt reads against its ref reads.
+    pub fn strand_bias1(&self) -> StrandBias {
+        let (f, r) = (&self.forward, &self.reverse);
+        StrandBias::new(
+            f.both + f.first,
+            r.both + r.first,
+            f.second + f.neither,
+            r.second + r.neither,
+        )
+    }
+
+    /// Strand bias of second variant alt reads against its ref reads.
+    pub fn strand_bias2(&self) -> StrandBias {
+        let (f, r) = (&self.forward, &self.reverse);
+        StrandBias::new(
+            f.both + f.second,
+            r.both + r.second,
+            f.first + f.neither,
+            r.first + r.neither,
+        )
+    }
+
+    /// Strand bias of reads supporting both variants against all other reads.
+    pub fn strand_bias_both(&self) -> StrandBias {
+        let (f, r) = (&self.forward, &self.reverse);
+        StrandBias::new(
+            f.both,
+            r.both,
+            f.first + f.second + f.neither,
+            r.first + r.second + r.neither,
+        )
+    }
+
+    /// Any read count supporting linkage is below `strand_reads` on either strand.
+    pub fn is_single_strand(&self, linkage: &Linkage, strand_reads: u32) -> bool {
+        let cells = |v: &StrandCounts| match linkage {
+            Linkage::Cis => vec![v.both],
+            Linkage::Trans => vec![v.first, v.second],
+            Linkage::Super => vec![v.both, v.first],
+            Linkage::Sub => vec![v.both, v.second],
+            Linkage::Cross => vec![v.both, v.first, v.second],
+        };
+        cells(&self.forward)
+            .into_iter()
+            .chain(cells(&self.reverse))
+            .any(|v| v < strand_reads)
+    }
+
     /// Infer linkage based on link.
-    pub fn infer_linkage(&self) -> Option<Linkage> {
-        if self.both >= VALID_READ
-            && self.both_freq_any() >= CONF_FREQ
-            && self.major() < VALID_READ
-            && self.major_freq_any() < VALID_FREQ
+    pub fn infer_linkage(&self, thresholds: &LinkageThresholds) -> Option<Linkage> {
+        let valid_freq = thresholds.valid_freq;
+        let conf_freq = thresholds.conf_freq;
+        let (cis, trans, sup, sub, cross) = (
+            thresholds.cis_reads,
+            thresholds.trans_reads,
+            thresholds.super_reads,
+            thresholds.sub_reads,
+            thresholds.cross_reads,
+        );
+        if self.both >= cis
+            && self.both_freq_any() >= conf_freq
+            && self.major() < cis
+            && self.major_freq_any() < valid_freq
         {
             Some(Linkage::Cis)
-        } else if self.both < VALID_READ
-            && self.both_freq_any() < VALID_FREQ
-            && self.minor() >= VALID_READ
-            && self.minor_freq_both() >= CONF_FREQ
+        } else if self.both < trans
+            && self.both_freq_any() < valid_freq
+            && self.minor() >= trans
+            && self.minor_freq_both() >= conf_freq
         {
             Some(Linkage::Trans)
-        } else if self.both >= VALID_READ
-            && self.first < VALID_READ
-            && self.first_freq_any() < VALID_FREQ
-            && self.second >= VALID_READ
+        } else if self.both >= sub
+            && self.first < sub
+            && self.first_freq_any() < valid_freq
+            && self.second >= sub
         {
             Some(Linkage::Sub)
-        } else if self.both >= VALID_READ
-            && self.second < VALID_READ
-            && self.second_freq_any() < VALID_FREQ
-            && self.first >= VALID_READ
+        } else if self.both >= sup
+            && self.second < sup
+            && self.second_freq_any() < valid_freq
+            && self.first >= sup
         {
             Some(Linkage::Super)
-        } else if self.both >= VALID_READ
-            && self.both_freq_any() >= VALID_FREQ
-            && self.minor() >= VALID_READ
-            && self.minor_freq_any() >= VALID_FREQ
+        } else if self.both >= cross
+            && self.both_freq_any() >= valid_freq
+            && self.minor() >= cross
+            && self.minor_freq_any() >= valid_freq
         {
             Some(Linkage::Cross)
         } else {
             None
         }
     }
-}
 
-impl Display for Link {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(v) = self.infer_linkage() {
-            write!(
-                f,
-                "{{\n  \"both\": {},\n  \"first\": {},\n  \"second\": {},\n  \"neither\": {},\n  \"conclusion\": \"{}\"\n}}",
-                self.both, self.first, self.second, self.neither, v
-            )
-        } else {
-            write!(
-                f,
-                "{{\n  \"both\": {},\n  \"first\": {},\n  \"second\": {},\n  \"neither\": {},\n  \"conclusion\": \"undefined\"\n}}",
-                self.both, self.first, self.second, self.neither
-            )
+    /// Counts with derived statistics and linkage inferred by thresholds.
+    pub fn stats(&self, thresholds: &LinkageThresholds) -> LinkStats {
+        let conclusion = self.infer_linkage(thresholds);
+        LinkStats {
+            counts: self.clone(),
+            d: self.d(),
+            d_prime: self.d_prime(),
+            r2: self.r2(),
+            odds_ratio: self.odds_ratio(),
+            p_value: self.p_value(),
+            strand_bias1: self.strand_bias1(),
+            strand_bias2: self.strand_bias2(),
+            strand_bias_both: self.strand_bias_both(),
+            alt_position1: median(&self.positions[0]),
+            alt_position2: median(&self.positions[1]),
+            single_strand: conclusion
+                .as_ref()
+                .is_some_and(|v| self.is_single_strand(v, thresholds.strand_reads)),
+            conclusion,
         }
     }
 }
 
+/// Link counts with all derived metrics, serialized flat.
+#[derive(Debug, Serialize)]
+pub struct LinkStats {
+    #[serde(flatten)]
+    pub counts: Link,
+    pub d: Option<f64>,
+    pub d_prime: Option<f64>,
+    pub r2: Option<f64>,
+    pub odds_ratio: Option<f64>,
+    pub p_value: f64,
+    pub strand_bias1: StrandBias,
+    pub strand_bias2: StrandBias,
+    pub strand_bias_both: StrandBias,
+    // Median position in read of each variant in reads supporting it, `None` if no such read.
+    pub alt_position1: Option<f64>,
+    pub alt_position2: Option<f64>,
+    // Linkage inferred by thresholds, `None` if undefined.
+    pub conclusion: Option<Linkage>,
+    // Conclusion supported by a single strand, by `strand_reads` of thresholds.
+    pub single_strand: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,8 +511,12 @@ mod tests {
             first: 1,
             second: 0,
             neither: 100,
+            ..Default::default()
         };
-        assert_eq!(link.infer_linkage(), Some(Linkage::Cis));
+        assert_eq!(
+            link.infer_linkage(&LinkageThresholds::default()),
+            Some(Linkage::Cis)
+        );
     }
 
     #[test]
@@ -164,8 +526,12 @@ mod tests {
             first: 50,
             second: 70,
             neither: 100,
+            ..Default::default()
         };
-        assert_eq!(link.infer_linkage(), Some(Linkage::Trans));
+        assert_eq!(
+            link.infer_linkage(&LinkageThresholds::default()),
+            Some(Linkage::Trans)
+        );
     }
 
     #[test]
@@ -175,8 +541,12 @@ mod tests {
             first: 50,
             second: 0,
             neither: 100,
+            ..Default::default()
         };
-        assert_eq!(link.infer_linkage(), Some(Linkage::Super));
+        assert_eq!(
+            link.infer_linkage(&LinkageThresholds::default()),
+            Some(Linkage::Super)
+        );
     }
 
     #[test]
@@ -186,7 +556,127 @@ mod tests {
             first: 1,
             second: 50,
             neither: 100,
+            ..Default::default()
+        };
+        assert_eq!(
+            link.infer_linkage(&LinkageThresholds::default()),
+            Some(Linkage::Sub)
+        );
+    }
+
+    #[test]
+    fn test_infer_thresholds() {
+        let link = Link {
+            both: 2,
+            first: 0,
+            second: 0,
+            neither: 1000,
+            ..Default::default()
+        };
+        let mut thresholds = LinkageThresholds::default();
+        assert_eq!(link.infer_linkage(&thresholds), None);
+        thresholds.cis_reads = 2;
+        assert_eq!(link.infer_linkage(&thresholds), Some(Linkage::Cis));
+        let thresholds: LinkageThresholds = toml::from_str("cis_reads = 2").unwrap();
+        assert_eq!(thresholds.trans_reads, 3);
+        assert_eq!(link.infer_linkage(&thresholds), Some(Linkage::Cis));
+        assert!(toml::from_str::<LinkageThresholds>("cis_read = 2").is_err());
+        assert!(matches!(
+            LinkageThresholds::parse("cis_reads = 2\ntrans_reads = two"),
+            Err(Error::Parse { input, offset: 14 }) if input == "trans_reads = two"
+        ));
+    }
+
+    #[test]
+    fn test_ld_statistics() {
+        let link = Link {
+            both: 40,
+            first: 10,
+            second: 10,
+            neither: 40,
+            ..Default::default()
+        };
+        assert!((link.d().unwrap() - 0.15).abs() < 1e-12);
+        assert!((link.d_prime().unwrap() - 0.6).abs() < 1e-12);
+        assert!((link.r2().unwrap() - 0.36).abs() < 1e-12);
+        assert!((link.odds_ratio().unwrap() - 16.0).abs() < 1e-12);
+        assert!((link.p_value() - 2.2221008e-9).abs() < 1e-15);
+        let link = Link {
+            both: 0,
+            first: 50,
+            second: 50,
+            neither: 0,
+            ..Default::default()
+        };
+        assert!((link.d_prime().unwrap() + 1.0).abs() < 1e-12);
+        assert!((link.r2().unwrap() - 1.0).abs() < 1e-12);
+        assert!(Link::default().d().is_none());
+    }
+
+    #[test]
+    fn test_table() {
+        let mut link = Link::default();
+        link.add(Allele::Alt, Allele::Ref, false);
+        link.add(Allele::Other, Allele::Ref, true);
+        link.add(Allele::Other, Allele::Ref, true);
+        link.add(Allele::Ref, Allele::Ref, false);
+        assert_eq!(link.table(), [[0, 1, 0], [0, 1, 0], [0, 2, 0]]);
+        assert_eq!(link.count(Allele::Other, Allele::Ref), 2);
+        assert_eq!(link.other(), 2);
+        // Other alleles are not counted into LD statistics.
+        assert_eq!(
+            link.odds_ratio(),
+            Link {
+                first: 1,
+                neither: 1,
+                ..Default::default()
+            }
+            .odds_ratio()
+        );
+    }
+
+    #[test]
+    fn test_strand() {
+        let mut link = Link::default();
+        for _ in 0..10 {
+            link.add(Allele::Alt, Allele::Alt, false);
+            link.add(Allele::Ref, Allele::Ref, false);
+            link.add(Allele::Ref, Allele::Ref, true);
+        }
+        link.add(Allele::Alt, Allele::Other, true);
+        assert_eq!(
+            (link.forward.both, link.reverse.both, link.reverse.neither),
+            (10, 0, 10)
+        );
+        let bias = link.strand_bias_both();
+        assert!(bias.p_value < 0.05 && bias.sor > 2.0);
+        assert_eq!(link.strand_bias1(), bias);
+        assert!(!link.is_single_strand(&Linkage::Cis, 0));
+        assert!(link.is_single_strand(&Linkage::Cis, 1));
+        let mut thresholds = LinkageThresholds::default();
+        assert!(!link.stats(&thresholds).single_strand);
+        thresholds.strand_reads = 1;
+        let stats = link.stats(&thresholds);
+        assert_eq!(stats.conclusion, Some(Linkage::Cis));
+        assert!(stats.single_strand);
+    }
+
+    #[test]
+    fn test_stats_serialize() {
+        let link = Link {
+            both: 101,
+            first: 1,
+            second: 0,
+            neither: 100,
+            ..Default::default()
         };
-        assert_eq!(link.infer_linkage(), Some(Linkage::Sub));
+        let value = serde_json::to_value(link.stats(&LinkageThresholds::default())).unwrap();
+        assert_eq!(value["both"], 101);
+        assert_eq!(value["conclusion"], "cis");
+        assert!(value["p_value"].is_f64());
+        let value = serde_json::to_value(Link::default().stats(&LinkageThresholds::default()));
+        let value = value.unwrap();
+        assert!(value["d"].is_null());
+        assert!(value["conclusion"].is_null());
     }
 }
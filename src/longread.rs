@@ -0,0 +1,161 @@
+use std::cmp::Ordering;
+
+use bam::record::Record;
+
+use crate::softclip::apply;
+use crate::{Reason, Variant};
+
+/// Reference bases compared on each side of variant.
+const WINDOW: u32 = 15;
+/// Max edit distance per base of the better haplotype, noisier reads are not used.
+const MAX_ERROR_RATE: f32 = 0.3;
+
+/// Collapse homopolymer runs into single bases.
+fn compress(seq: &[u8]) -> Vec<u8> {
+    let mut seq = seq.to_vec();
+    seq.dedup();
+    seq
+}
+
+/// Edit distance, `N` matches any base.
+fn distance(read: &[u8], hap: &[u8]) -> usize {
+    let mut prev: Vec<usize> = (0..=hap.len()).collect();
+    for (i, r) in read.iter().enumerate() {
+        let mut curr = vec![i + 1; hap.len() + 1];
+        for (j, h) in hap.iter().enumerate() {
+            let cost = usize::from(!(r == h || *r == b'N' || *h == b'N'));
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[hap.len()]
+}
+
+/// Window range of variant and the homopolymer runs next to it, whose lengths are kept.
+fn run_span(variant: &Variant, lo: u32, window: &[u8]) -> (usize, usize) {
+    let (left, right) = if variant.edit().is_ins() {
+        (variant.start() + 1 - lo, variant.start() + 1 - lo)
+    } else {
+        (variant.start() - lo, variant.end() + 1 - lo)
+    };
+    let (mut l, mut r) = (left as usize, (right as usize).min(window.len()));
+    if l > 0 {
+        l -= 1;
+        while l > 0 && window[l - 1] == window[l] {
+            l -= 1;
+        }
+    };
+    if r < window.len() {
+        r += 1;
+        while r < window.len() && window[r] == window[r - 1] {
+            r += 1;
+        }
+    };
+    (l, r)
+}
+
+/// Sequence with homopolymer runs collapsed, except in range `l..r`.
+fn compress_flanks(seq: &[u8], (l, r): (usize, usize)) -> Vec<u8> {
+    let mut v = compress(&seq[..l]);
+    v.extend_from_slice(&seq[l..r]);
+    v.extend(compress(&seq[r..]));
+    v
+}
+
+/// Validate long read by edit distance of its bases around variant to reference and variant
+/// haplotypes, all with homopolymer runs collapsed so that their length errors are ignored,
+/// except for the runs at variant, so that homopolymer length changes are still told apart.
+pub(crate) fn validate(record: &Record, variant: &Variant, min_base_qual: u8) -> Reason {
+    // 1-based window of reference.
+    let lo = variant.start().saturating_sub(WINDOW).max(1);
+    let hi = variant.end() + WINDOW;
+    let quals = record.qualities();
+    let quals = if quals.available() { quals.raw() } else { &[] };
+    let mut window = Vec::new();
+    // Read bases, keyed by twice the reference position, plus one for insertions after it.
+    let mut read = Vec::new();
+    // Last reference position seen, to place insertions.
+    let mut prev: Option<u32> = None;
+    let entries = match record.alignment_entries() {
+        Ok(v) => v,
+        Err(_) => return Reason::NotCovered,
+    };
+    for entry in entries {
+        let pos = entry.ref_pos().map(|v| v + 1);
+        let inside = match pos {
+            Some(v) => (lo..=hi).contains(&v),
+            None => prev.is_some_and(|v| v >= lo && v < hi),
+        };
+        if let Some(v) = pos {
+            if v > hi {
+                break;
+            };
+            prev = Some(v);
+        };
+        if !inside {
+            continue;
+        };
+        if let Some(nt) = pos.and(entry.ref_nt()) {
+            window.push(nt);
+        };
+        if let (Some(i), Some(nt)) = (entry.record_pos(), entry.record_nt()) {
+            let low = quals.get(i as usize).is_some_and(|v| *v < min_base_qual);
+            let key = pos.map_or_else(|| prev.unwrap_or(0) * 2 + 1, |v| v * 2);
+            read.push((key, if low { b'N' } else { nt }));
+        };
+    }
+    if window.len() as u32 != hi - lo + 1 {
+        return Reason::TooShort;
+    };
+    let alt = match apply(variant, lo, &window) {
+        Some(v) => v,
+        None => return Reason::TooShort,
+    };
+    let (l, r) = run_span(variant, lo, &window);
+    // Read bases at reference positions of runs, and insertions next to them.
+    let (first, last) = ((lo + l as u32) * 2 - 1, (lo + r as u32 - 1) * 2 + 1);
+    let read_l = read.iter().take_while(|(k, _)| *k < first).count();
+    let read_r = read_l
+        + read[read_l..]
+            .iter()
+            .take_while(|(k, _)| *k <= last)
+            .count();
+    let read: Vec<u8> = read.into_iter().map(|(_, nt)| nt).collect();
+    let read = compress_flanks(&read, (read_l, read_r));
+    let alt = compress_flanks(&alt, (l, r + alt.len() - window.len()));
+    let window = compress_flanks(&window, (l, r));
+    let (dist_ref, dist_alt) = (distance(&read, &window), distance(&read, &alt));
+    if dist_ref.min(dist_alt) as f32 > MAX_ERROR_RATE * window.len().max(alt.len()) as f32 {
+        return Reason::Noisy;
+    };
+    match dist_alt.cmp(&dist_ref) {
+        Ordering::Less => Reason::Supported,
+        Ordering::Greater => Reason::Reference,
+        Ordering::Equal => Reason::Ambiguous,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(compress(b"AAACGGT"), b"ACGT");
+        assert_eq!(distance(b"ACGT", b"ACGT"), 0);
+        assert_eq!(distance(b"ACNT", b"ACGT"), 0);
+        assert_eq!(distance(b"ACT", b"ACGT"), 1);
+        assert_eq!(distance(b"AGGT", b"ACGTA"), 2);
+    }
+
+    #[test]
+    fn test_run_span() {
+        // Runs `AAAAA` and `C` next to `G`, at 5-11 of `ACGTAAAAAGCTC`.
+        let window = b"ACGTAAAAAGCTC";
+        let var = Variant::from_hgvs("chrT:10G>T").unwrap();
+        assert_eq!(run_span(&var, 1, window), (4, 11));
+        let var = Variant::from_hgvs("chrT:9_10insA").unwrap();
+        assert_eq!(run_span(&var, 1, window), (4, 10));
+        assert_eq!(compress_flanks(b"AACCGGTT", (2, 4)), b"ACCGT");
+    }
+}
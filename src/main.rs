@@ -1,98 +1,665 @@
 #[macro_use]
 extern crate clap;
 extern crate bam;
+extern crate serde;
+extern crate serde_json;
 
-use std::fs::File;
-use std::io;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
 
-use bam::{IndexedReader, Region};
 use clap::{AppSettings, Clap};
+use serde::Serialize;
+use serde_json::Value;
 
-use varlink::{Link, ValidateOptions, VarFormat, Variant, VariantValidate};
+use varlink::{
+    count_haplotypes, count_link, find_vcf, merge_region, parse_flags, read_vcf, AlignmentReader,
+    Error, LinkStats, LinkageThresholds, Normalization, Pairing, Reference, Transcripts,
+    ValidateOptions, VarFormat, Variant,
+};
 
 #[derive(Clap)]
 #[clap(name = crate_name!(), version = crate_version!(), author = crate_authors!(), about = crate_description!())]
 #[clap(setting = AppSettings::ArgRequiredElseHelp)]
 struct Opts {
-    #[clap(short = '1', long, about = "First variant, in HGVS format.")]
-    first: String,
-    #[clap(short = '2', long, about = "Second variant, in HGVS format.")]
-    second: String,
+    #[clap(
+        short = '1',
+        long,
+        required_unless_present_any = &["vcf", "haplotype"],
+        about = "First variant, in HGVS format."
+    )]
+    first: Option<String>,
+    #[clap(
+        short = '2',
+        long,
+        required_unless_present_any = &["vcf", "haplotype"],
+        about = "Second variant, in HGVS format."
+    )]
+    second: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = &["first", "second", "haplotype"],
+        about = "Vcf file path, calculate linkage of variant pairs in it."
+    )]
+    vcf: Option<String>,
+    #[clap(
+        long,
+        about = "Vcf file path, find variants given by ID, contig:pos or contig:posREF>ALT in it."
+    )]
+    lookup: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with_all = &["first", "second"],
+        about = "Variants to count haplotypes of, in format given by --fmt."
+    )]
+    haplotype: Vec<String>,
+    #[clap(
+        long,
+        default_value = "window",
+        about = "Pairing strategy of vcf variants, window, anchor or adjacent."
+    )]
+    pairing: PairingKind,
+    #[clap(
+        long,
+        default_value = "500",
+        about = "Max distance between variants paired by window strategy."
+    )]
+    window: u32,
+    #[clap(
+        long,
+        required_if_eq("pairing", "anchor"),
+        about = "Anchor variant paired with all vcf variants, in format given by --fmt."
+    )]
+    anchor: Option<String>,
+    #[clap(
+        long,
+        about = "Output format of linkage or haplotype records, json, jsonl, tsv or csv. [default: json, jsonl for vcf]"
+    )]
+    output_format: Option<OutputFormat>,
+    #[clap(
+        long,
+        about = "Linkage thresholds config file in toml, overridden by threshold options."
+    )]
+    config: Option<String>,
+    #[clap(long, about = "Min read count of all linkage classes. [default: 3]")]
+    min_reads: Option<u32>,
+    #[clap(long, about = "Min read count of cis, overrides --min-reads.")]
+    cis_reads: Option<u32>,
+    #[clap(long, about = "Min read count of trans, overrides --min-reads.")]
+    trans_reads: Option<u32>,
+    #[clap(long, about = "Min read count of super, overrides --min-reads.")]
+    super_reads: Option<u32>,
+    #[clap(long, about = "Min read count of sub, overrides --min-reads.")]
+    sub_reads: Option<u32>,
+    #[clap(long, about = "Min read count of cross, overrides --min-reads.")]
+    cross_reads: Option<u32>,
+    #[clap(
+        long,
+        about = "Frequency under which read count is considered noise. [default: 0.01]"
+    )]
+    valid_freq: Option<f32>,
+    #[clap(
+        long,
+        about = "Frequency from which read count is considered confident. [default: 0.96]"
+    )]
+    conf_freq: Option<f32>,
+    #[clap(
+        long,
+        about = "Min read count on each strand of reads supporting the conclusion, flagged single_strand if below. [default: 0, not checked]"
+    )]
+    strand_reads: Option<u32>,
     #[clap(long, default_value = "hgvs", about = "Variant format, HGVS or Vcf.")]
     fmt: VarFormat,
-    #[clap(short, long, about = "Bam file path.")]
+    #[clap(
+        short,
+        long,
+        about = "Bam, cram or sam file path, unindexed bam or sam is read through, - for stdin."
+    )]
     bam: String,
+    #[clap(
+        short,
+        long,
+        about = "Indexed reference fasta path, required for cram, decoded by samtools."
+    )]
+    reference: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "reference",
+        about = "Shift indels in repeats by reference, left as vcf or right as hgvs 3' rule."
+    )]
+    normalize: Option<Normalization>,
+    #[clap(
+        long,
+        about = "Transcript annotation gtf or gff3 path, mapping transcript hgvs as NM_000546.6:c.215C>G."
+    )]
+    annotation: Option<PathBuf>,
     #[clap(
         long,
         about = "When *merge* variant of the target exists, do not count read as a support."
     )]
     merge: bool,
+    #[clap(
+        long,
+        about = "Count by fragment, merging support of reads with the same name."
+    )]
+    fragment: bool,
+    #[clap(
+        long,
+        about = "Realign reads to reference and variant haplotypes for indels, the better scoring one decides support, haplotypes from reference if given."
+    )]
+    realign: bool,
+    #[clap(
+        long,
+        default_value = "1000",
+        about = "Max insert size of pairs, longer pairs flanking a deletion support it, 0 to disable."
+    )]
+    max_insert: u32,
+    #[clap(
+        long,
+        about = "Long read profile, tolerate homopolymer errors and compare reads with reference and variant haplotypes by edit distance."
+    )]
+    long_read: bool,
+    #[clap(
+        long,
+        requires = "reference",
+        about = "Count reads with variant in soft clip, when clipped bases match only one of reference and variant haplotypes."
+    )]
+    soft_clip: bool,
+    #[clap(
+        long,
+        default_value = "0",
+        about = "Min base quality of read bases at variant, reads with lower ones are not counted."
+    )]
+    min_base_qual: u8,
+    #[clap(long, default_value = "0", about = "Min mapping quality of reads.")]
+    min_mapq: u8,
+    #[clap(
+        long,
+        default_value = "0",
+        about = "Min distance of variant from aligned read ends or soft clips, closer reads are not counted."
+    )]
+    end_distance: u32,
+    #[clap(
+        long,
+        default_value = "0",
+        parse(try_from_str = parse_flags),
+        about = "Only count reads with all of these flags, as number or names like PAIRED,PROPER_PAIR."
+    )]
+    include_flags: u16,
+    #[clap(
+        long,
+        default_value = "UNMAP,SECONDARY,QCFAIL,DUP",
+        parse(try_from_str = parse_flags),
+        about = "Skip reads with any of these flags, as number or names like UNMAP,DUP."
+    )]
+    exclude_flags: u16,
+}
+
+enum PairingKind {
+    Window,
+    Anchor,
+    Adjacent,
+}
+
+impl FromStr for PairingKind {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "window" => Ok(Self::Window),
+            "anchor" => Ok(Self::Anchor),
+            "adjacent" => Ok(Self::Adjacent),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum OutputFormat {
+    Json,
+    Jsonl,
+    Tsv,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::Jsonl),
+            "tsv" => Ok(Self::Tsv),
+            "csv" => Ok(Self::Csv),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, s)),
+        }
+    }
+}
+
+/// Options used for counting, reported with each record.
+#[derive(Serialize)]
+struct RunOptions<'a> {
+    #[serde(flatten)]
+    validate: &'a ValidateOptions,
+    fragment: bool,
+    normalize: Option<Normalization>,
+    thresholds: &'a LinkageThresholds,
+}
+
+/// Linkage of a variant pair, as output.
+#[derive(Serialize)]
+struct LinkRecord<'a> {
+    variant1: &'a str,
+    variant2: &'a str,
+    // Canonical hgvs of variants as counted.
+    hgvs1: String,
+    hgvs2: String,
+    region: String,
+    #[serde(flatten)]
+    stats: LinkStats,
+    variants: [&'a Variant; 2],
+    options: &'a RunOptions<'a>,
+}
+
+/// Flatten json value into fields, nested keys joined by `.`.
+fn flatten(key: String, value: Value, fields: &mut Vec<(String, String)>) {
+    let join = |k: &str| {
+        if key.is_empty() {
+            k.to_string()
+        } else {
+            format!("{}.{}", key, k)
+        }
+    };
+    match value {
+        Value::Object(v) => v
+            .into_iter()
+            .for_each(|(k, v)| flatten(join(&k), v, fields)),
+        Value::Array(v) => v
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, v)| flatten(join(&i.to_string()), v, fields)),
+        Value::Null => fields.push((key, String::from("NA"))),
+        Value::String(v) => fields.push((key, v)),
+        v => fields.push((key, v.to_string())),
+    }
+}
+
+/// Writer of output records, json records are written on finish, as an array if `batch`.
+struct RecordWriter<W: Write> {
+    out: W,
+    format: OutputFormat,
+    batch: bool,
+    records: Vec<Value>,
+    header: bool,
+}
+
+impl<W: Write> RecordWriter<W> {
+    fn new(out: W, format: OutputFormat, batch: bool) -> Self {
+        Self {
+            out,
+            format,
+            batch,
+            records: Vec::new(),
+            header: false,
+        }
+    }
+
+    fn write<T: Serialize>(&mut self, record: &T) -> Result<(), io::Error> {
+        let value = serde_json::to_value(record)?;
+        let sep = match self.format {
+            OutputFormat::Json => {
+                self.records.push(value);
+                return Ok(());
+            }
+            OutputFormat::Jsonl => return writeln!(self.out, "{}", value),
+            OutputFormat::Tsv => "\t",
+            OutputFormat::Csv => ",",
+        };
+        let mut fields = Vec::new();
+        flatten(String::new(), value, &mut fields);
+        let quote = |v: &str| {
+            if v.contains(sep) || v.contains('"') || v.contains('\n') {
+                format!("\"{}\"", v.replace('"', "\"\""))
+            } else {
+                v.to_string()
+            }
+        };
+        if !self.header {
+            let header: Vec<String> = fields.iter().map(|(k, _)| quote(k)).collect();
+            writeln!(self.out, "{}", header.join(sep))?;
+            self.header = true;
+        };
+        let row: Vec<String> = fields.iter().map(|(_, v)| quote(v)).collect();
+        writeln!(self.out, "{}", row.join(sep))
+    }
+
+    fn finish(mut self) -> Result<(), io::Error> {
+        if let OutputFormat::Json = self.format {
+            match self.records.as_slice() {
+                [v] if !self.batch => serde_json::to_writer_pretty(&mut self.out, v)?,
+                v => serde_json::to_writer_pretty(&mut self.out, v)?,
+            };
+            writeln!(self.out)?;
+        };
+        Ok(())
+    }
+}
+
+/// Region of variants, 1-based and closed.
+fn region_string(variants: &[&Variant]) -> String {
+    format!(
+        "{}:{}-{}",
+        variants.first().map_or("", |v| v.contig()),
+        variants.iter().map(|v| *v.start()).min().unwrap_or(0),
+        variants.iter().map(|v| *v.end()).max().unwrap_or(0)
+    )
 }
 
-fn get_merge_region(
-    bam: &IndexedReader<File>,
-    var1: &Variant,
-    var2: &Variant,
-) -> Result<Region, io::Error> {
-    if var1.contig() != var2.contig() {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!(
-                "Inconsistent contig: {} and {}.",
-                var1.contig(),
-                var2.contig()
-            ),
-        ));
+fn get_thresholds(opts: &Opts) -> Result<LinkageThresholds, Error> {
+    let mut thresholds = if let Some(v) = opts.config.as_deref() {
+        LinkageThresholds::from_path(v)?
+    } else {
+        LinkageThresholds::default()
+    };
+    if let Some(v) = opts.min_reads {
+        thresholds.set_min_reads(v);
     };
-    let ref_id = if let Some(v) = bam.header().reference_id(var1.contig()) {
-        v
+    thresholds.cis_reads = opts.cis_reads.unwrap_or(thresholds.cis_reads);
+    thresholds.trans_reads = opts.trans_reads.unwrap_or(thresholds.trans_reads);
+    thresholds.super_reads = opts.super_reads.unwrap_or(thresholds.super_reads);
+    thresholds.sub_reads = opts.sub_reads.unwrap_or(thresholds.sub_reads);
+    thresholds.cross_reads = opts.cross_reads.unwrap_or(thresholds.cross_reads);
+    thresholds.valid_freq = opts.valid_freq.unwrap_or(thresholds.valid_freq);
+    thresholds.conf_freq = opts.conf_freq.unwrap_or(thresholds.conf_freq);
+    thresholds.strand_reads = opts.strand_reads.unwrap_or(thresholds.strand_reads);
+    Ok(thresholds)
+}
+
+/// Reference opened on first use, filling duplications and normalizing variants, and transcript
+/// annotation mapping transcript hgvs.
+struct Normalizer {
+    path: Option<PathBuf>,
+    reference: Option<Reference>,
+    rule: Option<Normalization>,
+    transcripts: Option<Transcripts>,
+}
+
+impl Normalizer {
+    fn apply(&mut self, var: Variant) -> Result<Variant, Error> {
+        if !var.edit().is_dup() && self.rule.is_none() {
+            return Ok(var);
+        };
+        if self.reference.is_none() {
+            if let Some(path) = self.path.as_ref() {
+                self.reference = Some(Reference::from_path(path)?);
+            };
+        };
+        let reference = match self.reference.as_mut() {
+            Some(v) => v,
+            None if var.edit().is_dup() => {
+                return Err(Error::ReferenceRequired(format!(
+                    "duplication at {}:{}-{}",
+                    var.contig(),
+                    var.start(),
+                    var.end()
+                )))
+            }
+            None => return Ok(var),
+        };
+        let var = var.fill_dup(reference)?;
+        match self.rule {
+            Some(rule) => var.normalize(reference, rule),
+            None => Ok(var),
+        }
+    }
+}
+
+/// The only variant found for input, one allele of vcf record.
+fn single_variant<I: IntoIterator<Item = Variant>>(
+    input: &str,
+    found: I,
+) -> Result<Variant, Error> {
+    let mut found = found.into_iter();
+    match (found.next(), found.next()) {
+        (Some(v), None) => Ok(v),
+        _ => Err(Error::RecordNotFound(input.to_string())),
+    }
+}
+
+fn parse_variant(input: &str, opts: &Opts, normalizer: &mut Normalizer) -> Result<Variant, Error> {
+    let var = if let Some(path) = opts.lookup.as_deref() {
+        single_variant(input, find_vcf(path, input)?.into_iter().map(|(_, v)| v))?
+    } else if input.contains('\t') {
+        single_variant(input, Variant::from_vcf_line(input)?)?
+    } else if Transcripts::is_transcript(input) {
+        match normalizer.transcripts.as_ref() {
+            Some(v) => v.map(input)?,
+            None => return Err(Error::AnnotationRequired(input.to_string())),
+        }
     } else {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("No such id found for contig: {}.", var1.contig()),
-        ));
+        Variant::from(input, &opts.fmt)?
+    };
+    normalizer.apply(var)
+}
+
+/// Report a vcf pair skipped by error, the rest of batch goes on.
+fn skip_pair(variant1: &str, variant2: &str, err: &Error) {
+    eprintln!("Skipped {} and {}: {}", variant1, variant2, err);
+}
+
+fn run_batch(
+    opts: &Opts,
+    vcf: &str,
+    reader: &mut AlignmentReader,
+    options: &ValidateOptions,
+    mut reference: Option<&mut Reference>,
+    run_options: &RunOptions,
+    normalizer: &mut Normalizer,
+) -> Result<(), Error> {
+    let (mut names, variants): (Vec<String>, Vec<Variant>) = read_vcf(vcf)?.into_iter().unzip();
+    let mut variants = variants
+        .into_iter()
+        .map(|v| normalizer.apply(v))
+        .collect::<Result<Vec<Variant>, Error>>()?;
+    let pairing = match opts.pairing {
+        PairingKind::Window => Pairing::Window(opts.window),
+        PairingKind::Adjacent => Pairing::Adjacent,
+        PairingKind::Anchor => {
+            let input = opts.anchor.as_deref().unwrap_or_default();
+            let anchor = parse_variant(input, opts, normalizer)?;
+            if let Some(i) = variants.iter().position(|v| v == &anchor) {
+                Pairing::Anchor(i)
+            } else {
+                names.push(input.to_string());
+                variants.push(anchor);
+                Pairing::Anchor(variants.len() - 1)
+            }
+        }
     };
-    Ok(Region::new(
-        ref_id,
-        u32::min(*var1.start(), *var2.start()),
-        u32::max(*var1.end(), *var2.end()) + 1,
-    ))
+    // Pairs whose region fails, e.g. on a contig not in alignment header, are skipped.
+    let mut pairs = Vec::new();
+    let mut regions = Vec::new();
+    for (i, j) in pairing.pairs(&variants) {
+        match merge_region(
+            reader.header(),
+            &[&variants[i], &variants[j]],
+            options.flank(),
+        ) {
+            Ok(v) => {
+                pairs.push((i, j));
+                regions.push(v);
+            }
+            Err(e) => skip_pair(&names[i], &names[j], &e),
+        };
+    }
+    reader.prefetch(&regions)?;
+    let stdout = io::stdout();
+    let mut writer = RecordWriter::new(
+        stdout.lock(),
+        opts.output_format.unwrap_or(OutputFormat::Jsonl),
+        true,
+    );
+    for (i, j) in pairs {
+        let link = match count_link(
+            reader,
+            &variants[i],
+            &variants[j],
+            options,
+            reference.as_deref_mut(),
+            opts.fragment,
+        ) {
+            Ok(v) => v,
+            Err(e @ Error::Io(_)) | Err(e @ Error::Bam(_)) => return Err(e),
+            Err(e) => {
+                skip_pair(&names[i], &names[j], &e);
+                continue;
+            }
+        };
+        writer.write(&LinkRecord {
+            variant1: &names[i],
+            variant2: &names[j],
+            hgvs1: variants[i].to_hgvs(),
+            hgvs2: variants[j].to_hgvs(),
+            region: region_string(&[&variants[i], &variants[j]]),
+            stats: link.stats(run_options.thresholds),
+            variants: [&variants[i], &variants[j]],
+            options: run_options,
+        })?;
+    }
+    Ok(writer.finish()?)
 }
 
-fn main() -> Result<(), io::Error> {
+/// Exit code of each error.
+fn exit_code(err: &Error) -> i32 {
+    match err {
+        Error::Io(_) => 1,
+        Error::Parse { .. } => 3,
+        Error::UnknownContig(_) => 4,
+        Error::InconsistentContig(..) => 5,
+        Error::OutOfRange { .. } => 6,
+        Error::RefMismatch { .. } => 7,
+        Error::MissingMd(_) => 8,
+        Error::IndexMissing(_) => 9,
+        Error::Bam(_) => 10,
+        Error::ReferenceRequired(_) => 11,
+        Error::UnknownTranscript(_) => 12,
+        Error::AnnotationRequired(_) => 13,
+        Error::RecordNotFound(_) => 14,
+        Error::ProgramMissing(_) => 15,
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        process::exit(exit_code(&e));
+    };
+}
+
+fn run() -> Result<(), Error> {
     let opts: Opts = Opts::parse();
-    let mut link = Link::default();
-    let first = Variant::from(&opts.first, &opts.fmt)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?;
-    let second = Variant::from(&opts.second, &opts.fmt)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?;
-    let mut reader = IndexedReader::from_path(&opts.bam)?;
-    let region = get_merge_region(&reader, &first, &second)?;
+    let mut reader = AlignmentReader::from_path(&opts.bam, opts.reference.as_deref())?;
     let mut options = ValidateOptions::default();
     options.set_merge(opts.merge);
-    reader.fetch(&region)?.try_for_each(|rec| {
-        let rec = rec?;
-        let f1 = if let Some(v) = rec.validate(&first, &options) {
-            v
-        } else {
-            return Ok(());
-        };
-        let f2 = if let Some(v) = rec.validate(&second, &options) {
-            v
-        } else {
-            return Ok(());
-        };
-        if f1 && f2 {
-            link.both += 1;
-        } else if f1 {
-            link.first += 1;
-        } else if f2 {
-            link.second += 1;
-        } else {
-            link.neither += 1
-        };
-        Ok::<(), io::Error>(())
+    options.set_min_base_qual(opts.min_base_qual);
+    options.set_min_mapq(opts.min_mapq);
+    options.set_include_flags(opts.include_flags);
+    options.set_exclude_flags(opts.exclude_flags);
+    options.set_realign(opts.realign);
+    options.set_max_insert(opts.max_insert);
+    options.set_end_distance(opts.end_distance);
+    options.set_long_read(opts.long_read);
+    options.set_soft_clip(opts.soft_clip);
+    // Reference of soft clip evidence and realignment, opened once for all reads.
+    let mut reference = match (opts.soft_clip || opts.realign, opts.reference.as_deref()) {
+        (true, Some(path)) => Some(Reference::from_path(path)?),
+        _ => None,
+    };
+    let thresholds = get_thresholds(&opts)?;
+    let mut normalizer = Normalizer {
+        path: opts.reference.clone(),
+        reference: None,
+        rule: opts.normalize,
+        transcripts: opts
+            .annotation
+            .as_deref()
+            .map(Transcripts::from_path)
+            .transpose()?,
+    };
+    let run_options = RunOptions {
+        validate: &options,
+        fragment: opts.fragment,
+        normalize: opts.normalize,
+        thresholds: &thresholds,
+    };
+    if let Some(vcf) = opts.vcf.as_deref() {
+        return run_batch(
+            &opts,
+            vcf,
+            &mut reader,
+            &options,
+            reference.as_mut(),
+            &run_options,
+            &mut normalizer,
+        );
+    };
+    if !opts.haplotype.is_empty() {
+        let variants = opts
+            .haplotype
+            .iter()
+            .map(|v| parse_variant(v, &opts, &mut normalizer))
+            .collect::<Result<Vec<Variant>, Error>>()?;
+        let variants: Vec<&Variant> = variants.iter().collect();
+        let table = count_haplotypes(
+            &mut reader,
+            &variants,
+            &options,
+            reference.as_mut(),
+            opts.fragment,
+        )?;
+        let stdout = io::stdout();
+        let mut writer = RecordWriter::new(
+            stdout.lock(),
+            opts.output_format.unwrap_or(OutputFormat::Json),
+            false,
+        );
+        writer.write(&table)?;
+        return Ok(writer.finish()?);
+    };
+    let first = parse_variant(
+        opts.first.as_deref().unwrap_or_default(),
+        &opts,
+        &mut normalizer,
+    )?;
+    let second = parse_variant(
+        opts.second.as_deref().unwrap_or_default(),
+        &opts,
+        &mut normalizer,
+    )?;
+    let link = count_link(
+        &mut reader,
+        &first,
+        &second,
+        &options,
+        reference.as_mut(),
+        opts.fragment,
+    )?;
+    let stdout = io::stdout();
+    let mut writer = RecordWriter::new(
+        stdout.lock(),
+        opts.output_format.unwrap_or(OutputFormat::Json),
+        false,
+    );
+    writer.write(&LinkRecord {
+        variant1: opts.first.as_deref().unwrap_or_default(),
+        variant2: opts.second.as_deref().unwrap_or_default(),
+        hgvs1: first.to_hgvs(),
+        hgvs2: second.to_hgvs(),
+        region: region_string(&[&first, &second]),
+        stats: link.stats(&thresholds),
+        variants: [&first, &second],
+        options: &run_options,
     })?;
-    println!("{}", link);
-    Ok(())
+    Ok(writer.finish()?)
 }
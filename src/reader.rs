@@ -0,0 +1,405 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+use bam::bam_reader::RegionViewer;
+use bam::{BamReader, Header, IndexedReader, Record, Region, SamReader};
+
+use crate::Error;
+
+/// Command decoding cram into sam, with `MD` tags generated from reference.
+const SAMTOOLS: &str = "samtools";
+
+/// Cram file decoded by `samtools view` against a reference fasta.
+pub struct CramReader {
+    path: PathBuf,
+    reference: PathBuf,
+    header: Header,
+}
+
+impl CramReader {
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(SAMTOOLS);
+        cmd.arg("view")
+            .arg("-h")
+            .arg("-T")
+            .arg(&self.reference)
+            .arg(&self.path)
+            .stdout(Stdio::piped());
+        cmd
+    }
+
+    /// Open cram, header is read once and kept.
+    pub fn from_path<P: AsRef<Path>, Q: AsRef<Path>>(path: P, reference: Q) -> Result<Self, Error> {
+        // Samtools takes both `{path}.crai` and `{path%.cram}.crai`.
+        let path = path.as_ref();
+        if !PathBuf::from(format!("{}.crai", path.display())).exists()
+            && !path.with_extension("crai").exists()
+        {
+            return Err(Error::IndexMissing(path.to_path_buf()));
+        };
+        let mut reader = Self {
+            path: path.to_path_buf(),
+            reference: reference.as_ref().to_path_buf(),
+            header: Header::new(),
+        };
+        let mut child = spawn(reader.command().arg("-H"))?;
+        let stdout = child.stdout.take().expect("Samtools stdout is piped.");
+        reader.header = SamReader::from_stream(BufReader::new(stdout))
+            .map_err(Error::Bam)?
+            .header()
+            .clone();
+        check_status(&mut child).map_err(Error::Bam)?;
+        Ok(reader)
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Records overlapping region.
+    pub fn fetch(&self, region: &Region) -> Result<SamRecords, Error> {
+        let contig = self
+            .header
+            .reference_name(region.ref_id())
+            .ok_or_else(|| Error::UnknownContig(region.ref_id().to_string()))?;
+        // Samtools region is 1-based and closed.
+        let mut child = spawn(self.command().arg(format!(
+            "{}:{}-{}",
+            contig,
+            region.start() + 1,
+            region.end()
+        )))?;
+        let stdout = child.stdout.take().expect("Samtools stdout is piped.");
+        let reader = SamReader::from_stream(BufReader::new(stdout)).map_err(Error::Bam)?;
+        Ok(SamRecords {
+            reader,
+            child: Some(child),
+        })
+    }
+}
+
+/// Start samtools, told apart from a missing input if samtools is not installed.
+fn spawn(cmd: &mut Command) -> Result<Child, Error> {
+    cmd.spawn().map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::ProgramMissing(SAMTOOLS.to_string()),
+        _ => Error::Bam(e),
+    })
+}
+
+fn check_status(child: &mut Child) -> Result<(), io::Error> {
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} exited with {}.",
+            SAMTOOLS, status
+        )))
+    }
+}
+
+/// Records decoded from a `samtools view` process.
+pub struct SamRecords {
+    reader: SamReader<BufReader<ChildStdout>>,
+    child: Option<Child>,
+}
+
+impl Iterator for SamRecords {
+    type Item = Result<Record, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.next() {
+            Some(v) => Some(v),
+            // Surface decoding failure once all output is read.
+            None => self
+                .child
+                .take()
+                .and_then(|mut v| check_status(&mut v).err().map(Err)),
+        }
+    }
+}
+
+impl Drop for SamRecords {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        };
+    }
+}
+
+/// Record overlaps region.
+fn overlaps(record: &Record, region: &Region) -> bool {
+    record.ref_id() >= 0
+        && record.ref_id() as u32 == region.ref_id()
+        && (record.start() as u32) < region.end()
+        && (record.calculate_end() as u32) > region.start()
+}
+
+type RecordStream = Box<dyn Iterator<Item = Result<Record, io::Error>>>;
+
+/// Sam or unindexed bam, read through once from start to end.
+pub struct StreamReader {
+    header: Header,
+    stream: Option<RecordStream>,
+    // Records kept by prefetch.
+    cache: Option<Vec<Record>>,
+}
+
+impl StreamReader {
+    /// Open sam or bam stream, bam is recognized by its gzip magic number.
+    pub fn from_stream<R: Read + 'static>(stream: R) -> Result<Self, Error> {
+        let mut stream = BufReader::new(stream);
+        let is_bam = stream
+            .fill_buf()
+            .map_err(Error::Bam)?
+            .starts_with(&[0x1f, 0x8b]);
+        let (header, stream): (Header, RecordStream) = if is_bam {
+            let reader = BamReader::from_stream(stream, 0).map_err(Error::Bam)?;
+            (reader.header().clone(), Box::new(reader))
+        } else {
+            let reader = SamReader::from_stream(stream).map_err(Error::Bam)?;
+            (reader.header().clone(), Box::new(reader))
+        };
+        Ok(Self {
+            header,
+            stream: Some(stream),
+            cache: None,
+        })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Read through the stream, keep records overlapping any of the regions for later fetches.
+    pub fn prefetch(&mut self, regions: &[Region]) -> Result<(), Error> {
+        let mut cache = self.cache.take().unwrap_or_default();
+        if let Some(stream) = self.stream.take() {
+            for rec in stream {
+                let rec = rec.map_err(Error::Bam)?;
+                if regions.iter().any(|v| overlaps(&rec, v)) {
+                    cache.push(rec);
+                };
+            }
+        };
+        self.cache = Some(cache);
+        Ok(())
+    }
+
+    /// Records overlapping region, streamed if not prefetched, which is only possible once.
+    pub fn fetch(&mut self, region: &Region) -> Result<Records<'_>, Error> {
+        if let Some(v) = self.cache.as_ref() {
+            Ok(Records::Cached(v.iter(), region.clone()))
+        } else if let Some(v) = self.stream.take() {
+            Ok(Records::Stream(v, region.clone()))
+        } else {
+            Err(Error::Bam(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Stream input already read through, prefetch regions to fetch more than once.",
+            )))
+        }
+    }
+}
+
+/// Alignment file reader, indexed bam, cram, sam or unindexed bam.
+pub enum AlignmentReader {
+    Bam(IndexedReader<File>),
+    Cram(CramReader),
+    Stream(StreamReader),
+}
+
+impl AlignmentReader {
+    /// Open alignment file.
+    ///
+    /// - `-`: sam or bam from stdin.
+    /// - `*.cram`: decoded with reference fasta.
+    /// - bam with index `{path}.bai`: fetched by index.
+    /// - others: sam or unindexed bam, read through.
+    pub fn from_path<P: AsRef<Path>>(path: P, reference: Option<&Path>) -> Result<Self, Error> {
+        if path.as_ref() == Path::new("-") {
+            return Ok(Self::Stream(StreamReader::from_stream(io::stdin())?));
+        };
+        let has_index = PathBuf::from(format!("{}.bai", path.as_ref().display())).exists();
+        let is_cram = path
+            .as_ref()
+            .extension()
+            .is_some_and(|v| v.eq_ignore_ascii_case("cram"));
+        match (is_cram, reference) {
+            (true, Some(v)) => Ok(Self::Cram(CramReader::from_path(path, v)?)),
+            (true, None) => Err(Error::Bam(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Reference fasta is required for cram.",
+            ))),
+            (false, _) if has_index => Ok(Self::Bam(
+                IndexedReader::from_path(path).map_err(Error::Bam)?,
+            )),
+            (false, _) => Ok(Self::Stream(StreamReader::from_stream(
+                File::open(path).map_err(Error::Bam)?,
+            )?)),
+        }
+    }
+
+    pub fn header(&self) -> &Header {
+        match self {
+            Self::Bam(v) => v.header(),
+            Self::Cram(v) => v.header(),
+            Self::Stream(v) => v.header(),
+        }
+    }
+
+    /// Prepare for fetching regions more than once, only needed by stream input.
+    pub fn prefetch(&mut self, regions: &[Region]) -> Result<(), Error> {
+        match self {
+            Self::Stream(v) => v.prefetch(regions),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records overlapping region.
+    pub fn fetch(&mut self, region: &Region) -> Result<Records<'_>, Error> {
+        match self {
+            Self::Bam(v) => Ok(Records::Bam(v.fetch(region).map_err(Error::Bam)?)),
+            Self::Cram(v) => Ok(Records::Sam(v.fetch(region)?)),
+            Self::Stream(v) => v.fetch(region),
+        }
+    }
+}
+
+/// Iterator over records of any alignment reader.
+pub enum Records<'a> {
+    Bam(RegionViewer<'a, File>),
+    Sam(SamRecords),
+    Stream(RecordStream, Region),
+    Cached(std::slice::Iter<'a, Record>, Region),
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<Record, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Bam(v) => v.next(),
+            Self::Sam(v) => v.next(),
+            Self::Stream(v, region) => {
+                v.find(|rec| rec.as_ref().map_or(true, |rec| overlaps(rec, region)))
+            }
+            Self::Cached(v, region) => v.find(|rec| overlaps(rec, region)).cloned().map(Ok),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Allele, ValidateOptions, Variant, VariantValidate};
+
+    fn count(reader: &mut AlignmentReader, region: &Region) -> usize {
+        reader
+            .fetch(region)
+            .unwrap()
+            .collect::<Result<Vec<Record>, io::Error>>()
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_stream_input() {
+        let region = Region::new(0, 144854046, 144854049);
+        let mut indexed =
+            AlignmentReader::from_path("tests/triple-snp.1:144854047-144854049.bam", None).unwrap();
+        let mut sam =
+            AlignmentReader::from_path("tests/triple-snp.1:144854047-144854049.sam", None).unwrap();
+        assert!(matches!(indexed, AlignmentReader::Bam(_)));
+        assert!(matches!(sam, AlignmentReader::Stream(_)));
+        let expected = count(&mut indexed, &region);
+        assert!(expected > 0);
+        assert_eq!(count(&mut sam, &region), expected);
+        // Stream read through.
+        assert!(sam.fetch(&region).is_err());
+
+        let stream = File::open("tests/triple-snp.1:144854047-144854049.bam").unwrap();
+        let mut bam = AlignmentReader::Stream(StreamReader::from_stream(stream).unwrap());
+        bam.prefetch(std::slice::from_ref(&region)).unwrap();
+        assert_eq!(count(&mut bam, &region), expected);
+        assert_eq!(count(&mut bam, &region), expected);
+    }
+
+    #[test]
+    fn test_program_missing() {
+        let err = spawn(&mut Command::new("varlink-no-such-program"))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ProgramMissing(v) if v == SAMTOOLS));
+    }
+
+    /// Run samtools, false if samtools is not installed.
+    fn samtools(args: &[&str]) -> bool {
+        Command::new(SAMTOOLS)
+            .args(args)
+            .status()
+            .is_ok_and(|v| v.success())
+    }
+
+    #[test]
+    #[ignore = "requires samtools"]
+    fn test_cram_decode() {
+        let dir = std::env::temp_dir().join(format!("varlink-cram-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sam = dir.join("chrT.sam");
+        let cram = dir.join("chrT.cram");
+        // Read with mismatch 13C>T and no `MD` tag.
+        std::fs::write(
+            &sam,
+            "@SQ\tSN:chrT\tLN:60\nr1\t0\tchrT\t3\t60\t20M\t*\t0\t0\tGTAAAAAGCTTACACAGTTT\t*\n",
+        )
+        .unwrap();
+        let (sam, cram) = (sam.to_str().unwrap(), cram.to_str().unwrap());
+        assert!(samtools(&[
+            "view",
+            "-C",
+            "-T",
+            "tests/ref.fa",
+            "-o",
+            cram,
+            sam
+        ]));
+        assert!(samtools(&["index", cram]));
+
+        let mut reader = AlignmentReader::from_path(cram, Some(Path::new("tests/ref.fa"))).unwrap();
+        assert!(matches!(reader, AlignmentReader::Cram(_)));
+        let records = reader
+            .fetch(&Region::new(0, 12, 13))
+            .unwrap()
+            .collect::<Result<Vec<Record>, io::Error>>()
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(records.len(), 1);
+        // `MD` tag generated while decoding.
+        let var = Variant::from_hgvs("chrT:13C>T").unwrap();
+        let options = ValidateOptions::default();
+        assert_eq!(
+            records[0].validate(&var, &options).unwrap(),
+            Some(Allele::Alt)
+        );
+        assert!(reader
+            .fetch(&Region::new(0, 40, 50))
+            .unwrap()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_cram_requires_reference() {
+        let err = AlignmentReader::from_path("tests/missing.cram", None)
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Bam(e) if e.kind() == io::ErrorKind::InvalidInput));
+        let err = AlignmentReader::from_path("tests/missing.cram", Some(Path::new("tests/ref.fa")))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::IndexMissing(_)));
+    }
+}
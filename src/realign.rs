@@ -0,0 +1,170 @@
+use bam::record::Record;
+
+use crate::{Edit, Error, Reason, Reference, Variant};
+
+const MATCH: i32 = 2;
+const MISMATCH: i32 = -4;
+const GAP_OPEN: i32 = -6;
+const GAP_EXTEND: i32 = -1;
+
+/// Reference bases added on both sides of reference window, besides those deleted by variant.
+const FLANK: u32 = 5;
+
+/// Smith-Waterman local alignment score with affine gaps, `N` scores 0 against any base.
+fn local_align(read: &[u8], hap: &[u8]) -> i32 {
+    let width = hap.len() + 1;
+    let mut h = vec![0; width];
+    // Best score ending with a gap in read, per haplotype column.
+    let mut e = vec![i32::MIN / 2; width];
+    let mut best = 0;
+    for r in read {
+        let mut diag = 0;
+        // Best score ending with a gap in haplotype, along this row.
+        let mut f = i32::MIN / 2;
+        h[0] = 0;
+        for j in 1..width {
+            let score = if *r == b'N' || hap[j - 1] == b'N' {
+                0
+            } else if *r == hap[j - 1] {
+                MATCH
+            } else {
+                MISMATCH
+            };
+            e[j] = i32::max(e[j] + GAP_EXTEND, h[j] + GAP_OPEN);
+            f = i32::max(f + GAP_EXTEND, h[j - 1] + GAP_OPEN);
+            let curr = 0.max(diag + score).max(e[j]).max(f);
+            diag = h[j];
+            h[j] = curr;
+            best = best.max(curr);
+        }
+    }
+    best
+}
+
+/// Reference sequence of the aligned span of record from `MD` tag, with its 1-based start.
+fn reference_span(record: &Record) -> Option<(u32, Vec<u8>)> {
+    let mut refseq = Vec::new();
+    for entry in record.alignment_entries().ok()? {
+        if let Some(v) = entry.ref_nt() {
+            refseq.push(v);
+        };
+    }
+    Some((record.start() as u32 + 1, refseq))
+}
+
+/// Reference sequence around record, soft clips included, with its 1-based start.
+fn reference_window(
+    record: &Record,
+    variant: &Variant,
+    reference: &mut Reference,
+) -> Result<(u32, Vec<u8>), Error> {
+    let contig_len = reference
+        .contig_len(variant.contig())
+        .ok_or_else(|| Error::UnknownContig(variant.contig().to_string()))?;
+    let cigar = record.cigar();
+    let first = (record.start() as u32 + 1).saturating_sub(cigar.soft_clipping(true));
+    let last = record.calculate_end() as u32 + cigar.soft_clipping(false);
+    // Read showing deletion spans its deleted bases too.
+    let flank = if variant.edit().is_ins() {
+        FLANK
+    } else {
+        FLANK + variant.end() - variant.start() + 1
+    };
+    let lo = first.saturating_sub(flank).max(1);
+    let hi = (last + flank).min(contig_len);
+    Ok((lo, reference.fetch(variant.contig(), lo, hi)?))
+}
+
+/// Reference and variant haplotypes over reference span, `None` if variant is not inside.
+fn haplotypes(variant: &Variant, start: u32, refseq: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let end = start + refseq.len() as u32 - 1;
+    let altseq = variant.altseq().unwrap_or("").as_bytes();
+    let (left, right) = match variant.edit() {
+        // Inserted between start and end.
+        Edit::Ins if *variant.start() >= start && *variant.end() <= end => (
+            (variant.start() - start + 1) as usize,
+            (variant.end() - start) as usize,
+        ),
+        Edit::Del | Edit::Delins if *variant.start() > start && *variant.end() < end => (
+            (variant.start() - start) as usize,
+            (variant.end() - start + 1) as usize,
+        ),
+        _ => return None,
+    };
+    if variant
+        .refseq()
+        .is_some_and(|v| !v.as_bytes().eq_ignore_ascii_case(&refseq[left..right]))
+    {
+        return None;
+    };
+    let mut alt = refseq[..left].to_vec();
+    alt.extend_from_slice(altseq);
+    alt.extend_from_slice(&refseq[right..]);
+    Some((refseq.to_vec(), alt))
+}
+
+/// Validate record by aligning its whole sequence, soft clips included, to reference and variant
+/// haplotypes, the better scoring one decides the support.
+///
+/// Haplotypes are built from `reference` around the record if given, otherwise from the aligned
+/// span of record by its `MD` tag, which misses indels in soft clips.
+pub(crate) fn validate(
+    record: &Record,
+    variant: &Variant,
+    reference: Option<&mut Reference>,
+    min_base_qual: u8,
+) -> Result<Reason, Error> {
+    let span = match reference {
+        Some(v) => Some(reference_window(record, variant, v)?),
+        None => reference_span(record),
+    };
+    let (ref_hap, alt_hap) =
+        match span.and_then(|(start, refseq)| haplotypes(variant, start, &refseq)) {
+            Some(v) => v,
+            None => return Ok(Reason::TooShort),
+        };
+    let quals = record.qualities();
+    let mut read = record.sequence().to_vec();
+    if quals.available() {
+        for (nt, qual) in read.iter_mut().zip(quals.raw()) {
+            if *qual < min_base_qual {
+                *nt = b'N';
+            };
+        }
+    };
+    let ref_score = local_align(&read, &ref_hap);
+    let alt_score = local_align(&read, &alt_hap);
+    Ok(match alt_score.cmp(&ref_score) {
+        std::cmp::Ordering::Greater => Reason::Supported,
+        std::cmp::Ordering::Less => Reason::Reference,
+        std::cmp::Ordering::Equal => Reason::Ambiguous,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_align() {
+        assert_eq!(local_align(b"ACGT", b"TTACGTTT"), 8);
+        assert_eq!(local_align(b"ACGTACGT", b"ACGTTACGT"), 16 + GAP_OPEN);
+        assert_eq!(local_align(b"ACNT", b"ACGT"), 6);
+        assert_eq!(local_align(b"", b"ACGT"), 0);
+    }
+
+    #[test]
+    fn test_haplotypes() {
+        let var = Variant::from_hgvs("chrT:3_4insGG").unwrap();
+        let (r, a) = haplotypes(&var, 1, b"ACGTAC").unwrap();
+        assert_eq!(
+            (r.as_slice(), a.as_slice()),
+            (&b"ACGTAC"[..], &b"ACGGGTAC"[..])
+        );
+        let var = Variant::from_hgvs("chrT:3_4del").unwrap();
+        let (_, a) = haplotypes(&var, 1, b"ACGTAC").unwrap();
+        assert_eq!(a, b"ACAC");
+        let var = Variant::from_hgvs("chrT:6_7del").unwrap();
+        assert!(haplotypes(&var, 1, b"ACGTAC").is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::Allele;
+
+/// Why a read shows an allele of variant, or why it is not informative of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    // Read shows variant.
+    Supported,
+    // Split alignments flank deletion or inversion breakpoint.
+    Split,
+    // Pair insert spans deletion.
+    Discordant,
+    // Read shows reference.
+    Reference,
+    // Other base at variant.
+    Mismatch,
+    // Inserted bases differ from variant.
+    WrongInsertion,
+    // Insertion or deletion not in variant.
+    OtherIndel,
+    // Variant shown, but merged with a neighbor insertion, deletion or mismatch.
+    MergedNeighbor,
+    // Insertion, deletion or mismatch right before variant.
+    FlankMismatch,
+    // Read ends or stops partway through variant, evidence of no allele.
+    Partial,
+    // Read excluded by flags or mapping quality.
+    Filtered,
+    // Read not mapped.
+    Unmapped,
+    // Read without sequence.
+    NoSequence,
+    // Read does not overlap variant.
+    NotCovered,
+    // Read ends before variant is resolved.
+    TooShort,
+    // Base quality too low to decide.
+    LowQuality,
+    // Variant too close to aligned read end or soft clip.
+    NearEnd,
+    // Read matches both or neither of reference and variant haplotypes.
+    Ambiguous,
+    // Long read with too many errors around variant.
+    Noisy,
+}
+
+/// All reasons, in order.
+const REASONS: [Reason; 19] = [
+    Reason::Supported,
+    Reason::Split,
+    Reason::Discordant,
+    Reason::Reference,
+    Reason::Mismatch,
+    Reason::WrongInsertion,
+    Reason::OtherIndel,
+    Reason::MergedNeighbor,
+    Reason::FlankMismatch,
+    Reason::Partial,
+    Reason::Filtered,
+    Reason::Unmapped,
+    Reason::NoSequence,
+    Reason::NotCovered,
+    Reason::TooShort,
+    Reason::LowQuality,
+    Reason::NearEnd,
+    Reason::Ambiguous,
+    Reason::Noisy,
+];
+
+impl Reason {
+    /// Allele shown by read, `None` if read is not informative of variant.
+    pub fn allele(&self) -> Option<Allele> {
+        match self {
+            Self::Supported | Self::Split | Self::Discordant => Some(Allele::Alt),
+            Self::Reference => Some(Allele::Ref),
+            Self::Mismatch
+            | Self::WrongInsertion
+            | Self::OtherIndel
+            | Self::MergedNeighbor
+            | Self::FlankMismatch => Some(Allele::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Read counts by reason, every reason listed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reasons(BTreeMap<Reason, u32>);
+
+impl Default for Reasons {
+    fn default() -> Self {
+        Self(REASONS.iter().map(|v| (*v, 0)).collect())
+    }
+}
+
+impl Reasons {
+    /// Count a read by its reason.
+    pub fn add(&mut self, reason: Reason) {
+        *self.0.entry(reason).or_insert(0) += 1;
+    }
+
+    /// Read count of a reason.
+    pub fn count(&self, reason: Reason) -> u32 {
+        self.0.get(&reason).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reasons() {
+        let mut reasons = Reasons::default();
+        reasons.add(Reason::Mismatch);
+        reasons.add(Reason::Mismatch);
+        assert_eq!(reasons.count(Reason::Mismatch), 2);
+        assert_eq!(reasons.count(Reason::Supported), 0);
+        let value = serde_json::to_value(&reasons).unwrap();
+        assert_eq!(value.as_object().unwrap().len(), REASONS.len());
+        assert_eq!(value["mismatch"], 2);
+        assert_eq!(Reason::MergedNeighbor.allele(), Some(Allele::Other));
+        assert_eq!(Reason::Ambiguous.allele(), None);
+        assert_eq!(Reason::Partial.allele(), None);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::Error;
+
+/// Contig entry of fasta index.
+#[derive(Debug)]
+struct FaiEntry {
+    length: u64,
+    offset: u64,
+    line_bases: u64,
+    line_width: u64,
+}
+
+/// Fasta reader with samtools faidx index, `{fasta}.fai`.
+#[derive(Debug)]
+pub struct Reference {
+    file: File,
+    index: HashMap<String, FaiEntry>,
+}
+
+impl Reference {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let fai = format!("{}.fai", path.as_ref().display());
+        let fai = File::open(&fai).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => Error::IndexMissing(path.as_ref().to_path_buf()),
+            _ => Error::Io(e),
+        })?;
+        let mut index = HashMap::new();
+        for line in BufReader::new(fai).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            let invalid = || Error::Parse {
+                input: line.clone(),
+                offset: 0,
+            };
+            if fields.len() < 5 {
+                return Err(invalid());
+            };
+            let parse = |v: &str| v.parse::<u64>().map_err(|_| invalid());
+            index.insert(
+                fields[0].to_string(),
+                FaiEntry {
+                    length: parse(fields[1])?,
+                    offset: parse(fields[2])?,
+                    line_bases: parse(fields[3])?,
+                    line_width: parse(fields[4])?,
+                },
+            );
+        }
+        Ok(Self {
+            file: File::open(path)?,
+            index,
+        })
+    }
+
+    /// Contig length, `None` if not found.
+    pub fn contig_len(&self, contig: &str) -> Option<u32> {
+        self.index.get(contig).map(|v| v.length as u32)
+    }
+
+    /// Uppercase sequence of 1-based closed interval `start..=end`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::Reference;
+    ///
+    /// let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    /// assert_eq!(reference.fetch("chrT", 1, 5).unwrap(), b"ACGTA");
+    /// assert!(reference.fetch("chrT", 0, 5).is_err());
+    /// ```
+    pub fn fetch(&mut self, contig: &str, start: u32, end: u32) -> Result<Vec<u8>, Error> {
+        let entry = self
+            .index
+            .get(contig)
+            .ok_or_else(|| Error::UnknownContig(contig.to_string()))?;
+        if start == 0 || start > end || u64::from(end) > entry.length {
+            return Err(Error::OutOfRange {
+                contig: contig.to_string(),
+                start,
+                end,
+            });
+        };
+        let pos = |v: u32| {
+            let v = u64::from(v) - 1;
+            entry.offset + v / entry.line_bases * entry.line_width + v % entry.line_bases
+        };
+        let (first, last) = (pos(start), pos(end));
+        let mut buf = vec![0; (last - first + 1) as usize];
+        self.file.seek(SeekFrom::Start(first))?;
+        self.file.read_exact(&mut buf)?;
+        buf.retain(|v| !v.is_ascii_whitespace());
+        buf.make_ascii_uppercase();
+        Ok(buf)
+    }
+
+    /// Uppercase base at 1-based position.
+    pub fn base(&mut self, contig: &str, pos: u32) -> Result<u8, Error> {
+        Ok(self.fetch(contig, pos, pos)?[0])
+    }
+}
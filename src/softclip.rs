@@ -0,0 +1,145 @@
+use bam::record::Record;
+
+use crate::{Error, Reason, Reference, Variant};
+
+/// Reference bases replaced by variant, `0` for insertion.
+fn ref_len(variant: &Variant) -> u32 {
+    if variant.edit().is_ins() {
+        0
+    } else {
+        variant.end() - variant.start() + 1
+    }
+}
+
+/// Apply variant to reference sequence of 1-based window starting at `lo`, `None` if not inside.
+pub(crate) fn apply(variant: &Variant, lo: u32, window: &[u8]) -> Option<Vec<u8>> {
+    let (left, right) = if variant.edit().is_ins() {
+        (variant.start() + 1, *variant.start() + 1)
+    } else {
+        (*variant.start(), variant.end() + 1)
+    };
+    if left < lo || (right - lo) as usize > window.len() {
+        return None;
+    };
+    let (left, right) = ((left - lo) as usize, (right - lo) as usize);
+    if variant
+        .refseq()
+        .is_some_and(|v| !v.as_bytes().eq_ignore_ascii_case(&window[left..right]))
+    {
+        return None;
+    };
+    let mut alt = window[..left].to_vec();
+    alt.extend_from_slice(variant.altseq().unwrap_or("").as_bytes());
+    alt.extend_from_slice(&window[right..]);
+    Some(alt)
+}
+
+/// Clipped bases match haplotype, bases below min base quality match anything.
+fn matches(clip: &[u8], quals: &[u8], hap: &[u8], min_base_qual: u8) -> bool {
+    clip.len() == hap.len()
+        && clip
+            .iter()
+            .zip(hap)
+            .enumerate()
+            .all(|(i, (a, b))| a == b || quals.get(i).is_some_and(|v| *v < min_base_qual))
+}
+
+/// Validate record by its soft clipped bases against reference and variant haplotypes next to the
+/// clip, `None` if variant is not in soft clip.
+pub(crate) fn validate(
+    record: &Record,
+    variant: &Variant,
+    reference: &mut Reference,
+    min_base_qual: u8,
+) -> Result<Option<Reason>, Error> {
+    let contig_len = reference
+        .contig_len(variant.contig())
+        .ok_or_else(|| Error::UnknownContig(variant.contig().to_string()))?;
+    let seq = record.sequence().to_vec();
+    let quals = record.qualities();
+    let quals = if quals.available() { quals.raw() } else { &[] };
+    let left = record.cigar().soft_clipping(true);
+    let right = record.cigar().soft_clipping(false);
+    // 1-based first and last aligned reference position.
+    let first = record.start() as u32 + 1;
+    let last = record.calculate_end() as u32;
+    let is_ins = variant.edit().is_ins();
+
+    let (clip, clip_quals, ref_hap, alt_hap) =
+        if right > 0 && (*variant.start() > last || (is_ins && *variant.start() == last)) {
+            // Clip starts at reference position `last + 1`.
+            let lo = u32::min(last + 1, *variant.start());
+            let hi = u32::min(last + right + ref_len(variant), contig_len);
+            let window = reference.fetch(variant.contig(), lo, hi)?;
+            let alt = match apply(variant, lo, &window) {
+                Some(v) => v,
+                None => return Ok(None),
+            };
+            let skip = (last + 1 - lo) as usize;
+            let len = right as usize;
+            let take = |v: &[u8]| v.iter().skip(skip).take(len).copied().collect::<Vec<u8>>();
+            let from = seq.len() - len;
+            (
+                &seq[from..],
+                quals.get(from..).unwrap_or(&[]),
+                take(&window),
+                take(&alt),
+            )
+        } else if left > 0
+            && first > 1
+            && (*variant.end() < first || (is_ins && *variant.end() == first))
+        {
+            // Clip ends at reference position `first - 1`.
+            let hi = u32::max(first - 1, *variant.end());
+            let lo = (first - 1).saturating_sub(left + ref_len(variant)).max(1);
+            let window = reference.fetch(variant.contig(), lo, hi)?;
+            let alt = match apply(variant, lo, &window) {
+                Some(v) => v,
+                None => return Ok(None),
+            };
+            let skip = (hi + 1 - first) as usize;
+            let len = left as usize;
+            let take = |v: &[u8]| {
+                let v = &v[..v.len() - skip];
+                v[v.len().saturating_sub(len)..].to_vec()
+            };
+            (
+                &seq[..len],
+                quals.get(..len).unwrap_or(&[]),
+                take(&window),
+                take(&alt),
+            )
+        } else {
+            return Ok(None);
+        };
+
+    Ok(Some(
+        match (
+            matches(clip, clip_quals, &alt_hap, min_base_qual),
+            matches(clip, clip_quals, &ref_hap, min_base_qual),
+        ) {
+            (true, false) => Reason::Supported,
+            (false, true) => Reason::Reference,
+            _ => Reason::Ambiguous,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let window = b"ACGTAC";
+        let var = Variant::from_hgvs("chrT:3_4insGG").unwrap();
+        assert_eq!(apply(&var, 1, window).unwrap(), b"ACGGGTAC");
+        let var = Variant::from_hgvs("chrT:3_4del").unwrap();
+        assert_eq!(apply(&var, 1, window).unwrap(), b"ACAC");
+        let var = Variant::from_hgvs("chrT:3G>T").unwrap();
+        assert_eq!(apply(&var, 1, window).unwrap(), b"ACTTAC");
+        // Reference mismatch.
+        assert!(apply(&var, 2, window).is_none());
+        assert!(apply(&var, 4, window).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use bam::record::tags::TagValue;
+use bam::record::Record;
+
+/// Max distance of split alignment ends from breakpoints.
+pub(crate) const SPLIT_TOLERANCE: u32 = 5;
+
+/// Alignment of the other part of a split read, from `SA` tag.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SplitAlignment {
+    pub contig: String,
+    // 1-based first aligned reference position.
+    pub start: u32,
+    // 1-based last aligned reference position.
+    pub end: u32,
+    pub reverse: bool,
+    pub mapq: u8,
+}
+
+/// Reference length consumed by cigar string.
+fn ref_len(cigar: &str) -> Option<u32> {
+    let mut len = 0;
+    let mut num = 0;
+    for c in cigar.chars() {
+        if let Some(v) = c.to_digit(10) {
+            num = num * 10 + v;
+        } else {
+            if matches!(c, 'M' | 'D' | 'N' | '=' | 'X') {
+                len += num;
+            };
+            num = 0;
+        };
+    }
+    Some(len).filter(|v| *v > 0)
+}
+
+/// Parse `SA` tag entry `rname,pos,strand,CIGAR,mapQ,NM`.
+fn parse_entry(entry: &str) -> Option<SplitAlignment> {
+    let fields: Vec<&str> = entry.split(',').collect();
+    if fields.len() < 6 {
+        return None;
+    };
+    let start: u32 = fields[1].parse().ok()?;
+    Some(SplitAlignment {
+        contig: fields[0].to_string(),
+        start,
+        end: start + ref_len(fields[3])? - 1,
+        reverse: fields[2] == "-",
+        mapq: fields[4].parse().ok()?,
+    })
+}
+
+/// Other alignments of record from its `SA` tag, malformed entries are skipped.
+pub(crate) fn split_alignments(record: &Record) -> Vec<SplitAlignment> {
+    match record.tags().get(b"SA") {
+        Some(TagValue::String(v, _)) => String::from_utf8_lossy(v)
+            .split(';')
+            .filter_map(parse_entry)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        assert_eq!(
+            parse_entry("chrT,21,-,5S10M2D3M,60,1").unwrap(),
+            SplitAlignment {
+                contig: String::from("chrT"),
+                start: 21,
+                end: 35,
+                reverse: true,
+                mapq: 60,
+            }
+        );
+        assert!(parse_entry("chrT,21,+,5S,60,0").is_none());
+        assert!(parse_entry("chrT,x,+,10M,60,0").is_none());
+    }
+}
@@ -0,0 +1,112 @@
+/// Natural logarithm of factorials from `0!` to `n!`.
+fn ln_factorials(n: u32) -> Vec<f64> {
+    let mut table = Vec::with_capacity(n as usize + 1);
+    table.push(0.0);
+    for i in 1..=n {
+        table.push(table[i as usize - 1] + f64::from(i).ln());
+    }
+    table
+}
+
+/// Two-sided Fisher exact test p-value of 2×2 table `[[a, b], [c, d]]`.
+///
+/// ## Examples
+///
+/// ```rust
+/// use varlink::stats::fisher_exact;
+///
+/// assert!((fisher_exact(3, 1, 1, 3) - 0.4857).abs() < 1e-4);
+/// assert!((fisher_exact(10, 0, 0, 10) - 1.0825e-5).abs() < 1e-8);
+/// assert_eq!(fisher_exact(0, 0, 0, 0), 1.0);
+/// ```
+pub fn fisher_exact(a: u32, b: u32, c: u32, d: u32) -> f64 {
+    let n = a + b + c + d;
+    let row1 = a + b;
+    let col1 = a + c;
+    let lnf = ln_factorials(n);
+    // Log probability of table with top left cell x, margins fixed.
+    let ln_prob = |x: u32| -> f64 {
+        let (b, c) = (row1 - x, col1 - x);
+        let d = n - row1 - c;
+        lnf[row1 as usize]
+            + lnf[(n - row1) as usize]
+            + lnf[col1 as usize]
+            + lnf[(n - col1) as usize]
+            - lnf[n as usize]
+            - lnf[x as usize]
+            - lnf[b as usize]
+            - lnf[c as usize]
+            - lnf[d as usize]
+    };
+    let observed = ln_prob(a);
+    let lower = (row1 + col1).saturating_sub(n);
+    let upper = u32::min(row1, col1);
+    let p: f64 = (lower..=upper)
+        .map(ln_prob)
+        // Relative tolerance for tables as extreme as the observed one.
+        .filter(|v| *v <= observed + 1e-7)
+        .map(f64::exp)
+        .sum();
+    f64::min(p, 1.0)
+}
+
+/// Median of values, `None` if empty.
+///
+/// ## Examples
+///
+/// ```rust
+/// use varlink::stats::median;
+///
+/// assert_eq!(median(&[3, 1, 2]), Some(2.0));
+/// assert_eq!(median(&[4, 1, 2, 3]), Some(2.5));
+/// assert_eq!(median(&[]), None);
+/// ```
+pub fn median(values: &[u32]) -> Option<f64> {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(f64::from(values[mid])),
+        _ => Some((f64::from(values[mid - 1]) + f64::from(values[mid])) / 2.0),
+    }
+}
+
+/// Strand odds ratio of 2×2 table `[[ref_fwd, ref_rev], [alt_fwd, alt_rev]]`, as GATK `SOR`, with
+/// 1 added to each cell, higher for alt reads biased to one strand.
+///
+/// ## Examples
+///
+/// ```rust
+/// use varlink::stats::strand_odds_ratio;
+///
+/// assert!((strand_odds_ratio(10, 10, 10, 10) - 2f64.ln()).abs() < 1e-12);
+/// assert!(strand_odds_ratio(20, 20, 0, 15) > 3.0);
+/// ```
+pub fn strand_odds_ratio(ref_fwd: u32, ref_rev: u32, alt_fwd: u32, alt_rev: u32) -> f64 {
+    let (rf, rr) = (f64::from(ref_fwd) + 1.0, f64::from(ref_rev) + 1.0);
+    let (af, ar) = (f64::from(alt_fwd) + 1.0, f64::from(alt_rev) + 1.0);
+    let ratio = (rf / rr) * (ar / af);
+    let ref_ratio = rf.min(rr) / rf.max(rr);
+    let alt_ratio = af.min(ar) / af.max(ar);
+    (ratio + 1.0 / ratio).ln() + ref_ratio.ln() - alt_ratio.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fisher_exact() {
+        // R: fisher.test(matrix(c(1, 11, 9, 3), 2))$p.value
+        assert!((fisher_exact(1, 9, 11, 3) - 0.002759456).abs() < 1e-8);
+        assert!((fisher_exact(1, 9, 11, 3) - fisher_exact(9, 1, 3, 11)).abs() < 1e-12);
+        assert_eq!(fisher_exact(5, 0, 0, 0), 1.0);
+    }
+
+    #[test]
+    fn test_strand_odds_ratio() {
+        assert!((strand_odds_ratio(20, 20, 0, 15) - 5.549076084895219).abs() < 1e-12);
+        assert!((strand_odds_ratio(50, 45, 30, 2) - 4.475827417627203).abs() < 1e-12);
+    }
+}
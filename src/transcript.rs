@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use nom::bytes::complete::{tag, take_until};
+use nom::character::complete::{digit1, one_of};
+use nom::combinator::{map_res, opt};
+use nom::sequence::{preceded, tuple};
+use nom::IResult;
+
+use crate::{Error, Variant};
+
+/// Transcript model of annotation.
+#[derive(Debug, Clone, Default)]
+struct Transcript {
+    contig: String,
+    reverse: bool,
+    // Exons in transcript order, 1-based and closed.
+    exons: Vec<(u32, u32)>,
+    // Coding range including stop codon, 1-based and closed, `None` for non-coding transcript.
+    cds: Option<(u32, u32)>,
+}
+
+/// Anchor of transcript hgvs position.
+#[derive(Debug, PartialEq)]
+enum Anchor {
+    // `c.N` from first coding base, negative upstream as `c.-N`.
+    Cds(i64),
+    // `c.*N` downstream of stop codon.
+    Stop(i64),
+    // `n.N` from transcript start.
+    Transcript(i64),
+}
+
+/// Transcript hgvs position, with intronic offset as in `c.123+5`.
+#[derive(Debug, PartialEq)]
+struct Position {
+    anchor: Anchor,
+    offset: i64,
+}
+
+impl Transcript {
+    /// Transcript position of genomic position, `None` if not in exons.
+    fn transcript_pos(&self, pos: u32) -> Option<i64> {
+        let mut len = 0;
+        for &(start, end) in &self.exons {
+            if (start..=end).contains(&pos) {
+                let i = if self.reverse { end - pos } else { pos - start };
+                return Some(len + i64::from(i) + 1);
+            };
+            len += i64::from(end - start + 1);
+        }
+        None
+    }
+
+    /// Genomic position of transcript position, extended linearly beyond transcript ends.
+    fn genomic(&self, pos: i64) -> i64 {
+        let strand = if self.reverse { -1 } else { 1 };
+        let mut len = 0;
+        for &(start, end) in &self.exons {
+            let exon_len = i64::from(end - start + 1);
+            if pos <= len + exon_len {
+                let first = i64::from(if self.reverse { end } else { start });
+                return first + strand * (pos - len - 1);
+            };
+            len += exon_len;
+        }
+        let &(start, end) = self.exons.last().unwrap();
+        let last = i64::from(if self.reverse { start } else { end });
+        last + strand * (pos - len)
+    }
+
+    /// Genomic position of hgvs position, `None` for coding position of non-coding transcript.
+    fn resolve(&self, pos: &Position) -> Option<i64> {
+        let (cds_first, cds_last) = match self.cds {
+            Some((start, end)) if self.reverse => (end, start),
+            Some((start, end)) => (start, end),
+            None => (0, 0),
+        };
+        let pos_tx = match pos.anchor {
+            Anchor::Transcript(v) => v,
+            Anchor::Cds(v) if v > 0 => self.transcript_pos(cds_first)? + v - 1,
+            Anchor::Cds(v) => self.transcript_pos(cds_first)? + v,
+            Anchor::Stop(v) => self.transcript_pos(cds_last)? + v,
+        };
+        let pos_g = self.genomic(pos_tx);
+        Some(if self.reverse {
+            pos_g - pos.offset
+        } else {
+            pos_g + pos.offset
+        })
+    }
+}
+
+fn parse_number(input: &str) -> IResult<&str, i64> {
+    map_res(digit1, |v: &str| v.parse::<i64>())(input)
+}
+
+fn parse_position(input: &str, coding: bool) -> IResult<&str, Position> {
+    let (res, prefix) = if coding {
+        opt(one_of("-*"))(input)?
+    } else {
+        (input, None)
+    };
+    let (res, pos) = parse_number(res)?;
+    if pos == 0 {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )));
+    };
+    let (res, offset) = opt(tuple((one_of("+-"), parse_number)))(res)?;
+    let anchor = match prefix {
+        Some('-') => Anchor::Cds(-pos),
+        Some(_) => Anchor::Stop(pos),
+        None if coding => Anchor::Cds(pos),
+        None => Anchor::Transcript(pos),
+    };
+    let offset = match offset {
+        Some(('-', v)) => -v,
+        Some((_, v)) => v,
+        None => 0,
+    };
+    Ok((res, Position { anchor, offset }))
+}
+
+/// Parse transcript name, position range and remaining change, with the position input for
+/// error offsets.
+fn parse_transcript(input: &str) -> IResult<&str, (&str, &str, Position, Option<Position>)> {
+    let (res, name) = take_until(":")(input)?;
+    // Gene symbol as in `NM_000546.6(TP53)`.
+    let name = name.split('(').next().unwrap_or_default();
+    let (res, _) = tag(":")(res)?;
+    let (res, kind) = one_of("cn")(res)?;
+    let (pos_input, _) = tag(".")(res)?;
+    let coding = kind == 'c';
+    let (res, first) = parse_position(pos_input, coding)?;
+    let (res, last) = opt(preceded(tag("_"), |v| parse_position(v, coding)))(res)?;
+    Ok((res, (name, pos_input, first, last)))
+}
+
+/// Transcript models loaded from gtf or gff3 annotation, mapping transcript hgvs as
+/// `NM_000546.6:c.215C>G` to genomic variant.
+#[derive(Debug, Default)]
+pub struct Transcripts {
+    index: HashMap<String, Transcript>,
+}
+
+impl Transcripts {
+    /// Load exons and coding ranges of transcripts from gtf or gff3, told apart by attributes.
+    /// Transcripts are found by `transcript_id`, and also by `ID` or `Name` of gff3 parent.
+    /// Name without version gives the highest version of transcript.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut models: HashMap<String, Transcript> = HashMap::new();
+        let mut aliases = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            };
+            let fields: Vec<&str> = line.split('\t').collect();
+            let invalid = || Error::Parse {
+                input: line.clone(),
+                offset: 0,
+            };
+            if fields.len() < 9 {
+                return Err(invalid());
+            };
+            let start = fields[3].parse::<u32>().map_err(|_| invalid())?;
+            let end = fields[4].parse::<u32>().map_err(|_| invalid())?;
+            let attrs: Vec<(&str, &str)> = fields[8]
+                .split(';')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .filter_map(|v| {
+                    v.split_once('=')
+                        .or_else(|| v.split_once(' '))
+                        .map(|(k, v)| (k, v.trim().trim_matches('"')))
+                })
+                .collect();
+            let attr = |key: &str| attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+            let gff3 = fields[8].split(';').next().is_some_and(|v| v.contains('='));
+            if gff3 {
+                if let Some(id) = attr("ID") {
+                    for alias in [attr("Name"), attr("transcript_id")].iter().flatten() {
+                        aliases.push((alias.to_string(), id.to_string()));
+                    }
+                };
+            };
+            if !matches!(fields[2], "exon" | "CDS" | "stop_codon") {
+                continue;
+            };
+            let parents: Vec<&str> = if gff3 {
+                attr("Parent").map_or_else(Vec::new, |v| v.split(',').collect())
+            } else {
+                attr("transcript_id").into_iter().collect()
+            };
+            for parent in parents {
+                let model = models.entry(parent.to_string()).or_default();
+                model.contig = fields[0].to_string();
+                model.reverse = fields[6] == "-";
+                if fields[2] == "exon" {
+                    model.exons.push((start, end));
+                } else {
+                    model.cds = Some(model.cds.map_or((start, end), |(s, e)| {
+                        (u32::min(s, start), u32::max(e, end))
+                    }));
+                }
+            }
+        }
+
+        let mut index = HashMap::new();
+        for (id, mut model) in models {
+            if model.exons.is_empty() {
+                continue;
+            };
+            model.exons.sort_unstable();
+            if model.reverse {
+                model.exons.reverse();
+            };
+            index.insert(id, model);
+        }
+        for (alias, id) in aliases {
+            if let Some(model) = index.get(&id).cloned() {
+                index.entry(alias).or_insert(model);
+            };
+        }
+        // Also found without version, as its highest version, numeric versions compared by
+        // number so that `.10` comes after `.9`.
+        let mut unversioned: HashMap<&str, (&str, &Transcript)> = HashMap::new();
+        let version_key = |v: &str| (v.parse::<u32>().ok(), v.to_string());
+        for (key, model) in &index {
+            if let Some((name, version)) = key.rsplit_once('.') {
+                let best = unversioned.entry(name).or_insert((version, model));
+                if version_key(version) > version_key(best.0) {
+                    *best = (version, model);
+                };
+            };
+        }
+        let unversioned: Vec<(String, Transcript)> = unversioned
+            .into_iter()
+            .map(|(name, (_, model))| (name.to_string(), model.clone()))
+            .collect();
+        for (name, model) in unversioned {
+            index.entry(name).or_insert(model);
+        }
+        Ok(Self { index })
+    }
+
+    /// Transcript by name, falling back to name without version.
+    fn get(&self, name: &str) -> Option<&Transcript> {
+        self.index.get(name).or_else(|| {
+            name.rsplit_once('.')
+                .and_then(|(name, _)| self.index.get(name))
+        })
+    }
+
+    /// Input is transcript hgvs, `c.` or `n.`.
+    pub fn is_transcript(input: &str) -> bool {
+        input
+            .split_once(':')
+            .is_some_and(|(_, v)| v.starts_with("c.") || v.starts_with("n."))
+    }
+
+    /// Map transcript hgvs to genomic variant, intronic offsets and reverse strand included.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::{Transcripts, Variant};
+    ///
+    /// let transcripts = Transcripts::from_path("tests/annotation.gtf").unwrap();
+    /// // Reverse strand, first coding base at chrT:40.
+    /// let var = transcripts.map("NM_000002.1(TWO):c.1G>T").unwrap();
+    /// assert_eq!(var, Variant::from_hgvs("chrT:40C>A").unwrap());
+    /// let var = transcripts.map("NM_000002.1:c.10+1A>G").unwrap();
+    /// assert_eq!(var, Variant::from_hgvs("chrT:30T>C").unwrap());
+    /// ```
+    pub fn map(&self, input: &str) -> Result<Variant, Error> {
+        let (res, (name, pos_input, first, last)) =
+            parse_transcript(input).map_err(|e| Error::parse(input, e))?;
+        let transcript = self
+            .get(name)
+            .ok_or_else(|| Error::UnknownTranscript(name.to_string()))?;
+        let invalid = || Error::Parse {
+            input: input.to_string(),
+            offset: input.len() - pos_input.len(),
+        };
+        let first = transcript.resolve(&first).ok_or_else(invalid)?;
+        let last = match last {
+            Some(v) => transcript.resolve(&v).ok_or_else(invalid)?,
+            None => first,
+        };
+        let (start, end) = if transcript.reverse {
+            (last, first)
+        } else {
+            (first, last)
+        };
+        if start > end {
+            return Err(invalid());
+        };
+        if start < 1 || end > i64::from(u32::MAX) {
+            return Err(Error::OutOfRange {
+                contig: transcript.contig.clone(),
+                start: start.max(0) as u32,
+                end: end.clamp(0, i64::from(u32::MAX)) as u32,
+            });
+        };
+        Variant::parse_change(
+            transcript.contig.clone(),
+            start as u32,
+            end as u32,
+            res,
+            transcript.reverse,
+        )
+        .map_err(|e| Error::parse(input, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_position() {
+        let pos = |v| parse_position(v, true).unwrap().1;
+        assert_eq!(
+            pos("123+5"),
+            Position {
+                anchor: Anchor::Cds(123),
+                offset: 5
+            }
+        );
+        assert_eq!(
+            pos("-15-2"),
+            Position {
+                anchor: Anchor::Cds(-15),
+                offset: -2
+            }
+        );
+        assert_eq!(pos("*37").anchor, Anchor::Stop(37));
+        assert!(parse_position("0", true).is_err());
+        assert!(parse_position("*37", false).is_err());
+    }
+
+    #[test]
+    fn test_map() {
+        for path in &["tests/annotation.gtf", "tests/annotation.gff3"] {
+            let transcripts = Transcripts::from_path(path).unwrap();
+            let map = |v| transcripts.map(v).unwrap();
+            let hgvs = |v| Variant::from_hgvs(v).unwrap();
+            // Forward strand, exons 5-14, 21-35, 41-55 and coding 8-49.
+            assert_eq!(map("NM_000001.1:c.10G>A"), hgvs("chrT:23G>A"));
+            assert_eq!(map("NM_000001.1:c.7+2del"), hgvs("chrT:16del"));
+            assert_eq!(map("NM_000001.1:c.8-1del"), hgvs("chrT:20del"));
+            assert_eq!(map("NM_000001:c.-3del"), hgvs("chrT:5del"));
+            assert_eq!(map("NM_000001.1:c.-4del"), hgvs("chrT:4del"));
+            assert_eq!(map("NM_000001.1:c.*1_*2del"), hgvs("chrT:50_51del"));
+            // Reverse strand, exons 31-50, 3-20 and coding 7-40.
+            assert_eq!(map("NM_000002.1:c.1_2del"), hgvs("chrT:39_40del"));
+            assert_eq!(map("NM_000002.1:c.11-2del"), hgvs("chrT:22del"));
+            assert_eq!(map("NM_000002.1:c.*1del"), hgvs("chrT:6del"));
+            assert_eq!(map("NM_000002.1:c.1_2insGA"), hgvs("chrT:39_40insTC"));
+            // Non-coding.
+            assert_eq!(map("NR_000003.1:n.11del"), hgvs("chrT:21del"));
+            assert!(matches!(
+                transcripts.map("NR_000003.1:c.11del"),
+                Err(Error::Parse { offset: 14, .. })
+            ));
+            assert!(matches!(
+                transcripts.map("NM_000009.1:c.1del"),
+                Err(Error::UnknownTranscript(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_unversioned() {
+        let path = std::env::temp_dir().join(format!("varlink-gtf-{}.gtf", std::process::id()));
+        let line = |start, end, version| {
+            format!(
+                "chrT\ttest\texon\t{}\t{}\t.\t+\t.\tgene_id \"ONE\"; transcript_id \"NR_000001.{}\";\n",
+                start, end, version
+            )
+        };
+        let content = [line(1, 30, 2), line(11, 30, 10), line(21, 30, 9)].concat();
+        std::fs::write(&path, content).unwrap();
+        let transcripts = Transcripts::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Highest version by number, `.10` over `.9` and `.2`.
+        assert_eq!(
+            transcripts.map("NR_000001:n.1del").unwrap(),
+            Variant::from_hgvs("chrT:11del").unwrap()
+        );
+        assert_eq!(
+            transcripts.map("NR_000001.9:n.1del").unwrap(),
+            Variant::from_hgvs("chrT:21del").unwrap()
+        );
+    }
+}
@@ -1,18 +1,241 @@
 use bam::record::AlignmentEntry;
 use bam::record::Record;
+use serde::Serialize;
 
-use crate::{Edit, Variant};
+use crate::{deletion, inversion, longread, realign, softclip};
+use crate::{Edit, Error, Reason, Reference, Variant};
 
-#[derive(Debug, Default)]
+/// Allele of variant shown by a read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Allele {
+    // Reference allele.
+    Ref,
+    // Variant allele.
+    Alt,
+    // Third allele, other indel or sequencing error at variant.
+    Other,
+}
+
+/// Flag names in samtools convention.
+const FLAG_NAMES: [(&str, u16); 12] = [
+    ("PAIRED", 0x1),
+    ("PROPER_PAIR", 0x2),
+    ("UNMAP", 0x4),
+    ("MUNMAP", 0x8),
+    ("REVERSE", 0x10),
+    ("MREVERSE", 0x20),
+    ("READ1", 0x40),
+    ("READ2", 0x80),
+    ("SECONDARY", 0x100),
+    ("QCFAIL", 0x200),
+    ("DUP", 0x400),
+    ("SUPPLEMENTARY", 0x800),
+];
+
+/// Parse flags given in decimal, hexadecimal with `0x` prefix or comma separated names.
+///
+/// ## Examples
+///
+/// ```rust
+/// use varlink::parse_flags;
+///
+/// assert_eq!(parse_flags("1796").unwrap(), 0x704);
+/// assert_eq!(parse_flags("0x704").unwrap(), 0x704);
+/// assert_eq!(parse_flags("UNMAP,SECONDARY,QCFAIL,DUP").unwrap(), 0x704);
+/// assert!(parse_flags("UNMAPPED").is_err());
+/// assert!(matches!(
+///     parse_flags("DUP,UNMAPPED"),
+///     Err(varlink::Error::Parse { offset: 4, .. })
+/// ));
+/// ```
+pub fn parse_flags(input: &str) -> Result<u16, Error> {
+    let invalid = |offset| Error::Parse {
+        input: input.to_string(),
+        offset,
+    };
+    if let Some(v) = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+    {
+        return u16::from_str_radix(v, 16).map_err(|_| invalid(2));
+    };
+    if input.chars().all(|c| c.is_ascii_digit()) {
+        return input.parse::<u16>().map_err(|_| invalid(0));
+    };
+    let mut offset = 0;
+    input.split(',').try_fold(0, |flags, name| {
+        let flag = FLAG_NAMES
+            .iter()
+            .find(|(v, _)| v.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, v)| flags | v)
+            .ok_or_else(|| invalid(offset));
+        offset += name.len() + 1;
+        flag
+    })
+}
+
+#[derive(Debug, Serialize)]
 pub struct ValidateOptions {
     // If *merge* variants exists, do not consider record as support.
     merge: bool,
+    // Min base quality of read bases used to confirm or reject variant.
+    min_base_qual: u8,
+    // Min mapping quality of read.
+    min_mapq: u8,
+    // Read with any of these flags unset is skipped.
+    include_flags: u16,
+    // Read with any of these flags set is skipped.
+    exclude_flags: u16,
+    // Realign read to reference and variant haplotypes for indels.
+    realign: bool,
+    // Long read profile, tolerating homopolymer length and other sequencing errors around variant.
+    long_read: bool,
+    // Compare soft clipped bases with reference and variant haplotypes.
+    soft_clip: bool,
+    // Max insert size of pairs, longer pairs flanking deletion support it, 0 to disable.
+    max_insert: u32,
+    // Min distance of variant from aligned read ends or soft clips, closer reads are not used.
+    end_distance: u32,
+}
+
+/// Max distance of reads fetched around variants for soft clip evidence and realignment.
+const SOFT_CLIP_FLANK: u32 = 300;
+
+impl Default for ValidateOptions {
+    fn default() -> Self {
+        Self {
+            merge: false,
+            min_base_qual: 0,
+            min_mapq: 0,
+            include_flags: 0,
+            // UNMAP, SECONDARY, QCFAIL and DUP, as samtools mpileup.
+            exclude_flags: 0x704,
+            realign: false,
+            long_read: false,
+            soft_clip: false,
+            max_insert: 1000,
+            end_distance: 0,
+        }
+    }
 }
 
 impl ValidateOptions {
     pub fn set_merge(&mut self, merge: bool) {
         self.merge = merge;
     }
+
+    pub fn set_min_base_qual(&mut self, min_base_qual: u8) {
+        self.min_base_qual = min_base_qual;
+    }
+
+    pub fn set_min_mapq(&mut self, min_mapq: u8) {
+        self.min_mapq = min_mapq;
+    }
+
+    pub fn set_include_flags(&mut self, include_flags: u16) {
+        self.include_flags = include_flags;
+    }
+
+    pub fn set_exclude_flags(&mut self, exclude_flags: u16) {
+        self.exclude_flags = exclude_flags;
+    }
+
+    pub fn set_realign(&mut self, realign: bool) {
+        self.realign = realign;
+    }
+
+    /// Long read profile, reads are compared with reference and variant haplotypes around variant
+    /// by edit distance with homopolymer runs away from variant collapsed, instead of exact matching.
+    pub fn set_long_read(&mut self, long_read: bool) {
+        self.long_read = long_read;
+    }
+
+    /// Use soft clipped bases as evidence, compared with reference and variant haplotypes, the
+    /// reference is passed to [`VariantValidate::reason_with_reference`].
+    pub fn set_soft_clip(&mut self, soft_clip: bool) {
+        self.soft_clip = soft_clip;
+    }
+
+    /// Count pairs with insert longer than `max_insert` but not without deletion as its support,
+    /// 0 to disable.
+    pub fn set_max_insert(&mut self, max_insert: u32) {
+        self.max_insert = max_insert;
+    }
+
+    /// Ignore reads with variant within `end_distance` bases of their aligned ends or soft clips,
+    /// or of their clipped ends for variant in soft clip, 0 to disable.
+    pub fn set_end_distance(&mut self, end_distance: u32) {
+        self.end_distance = end_distance;
+    }
+
+    /// Distance around variants to fetch reads which may support them.
+    pub fn flank(&self) -> u32 {
+        if self.soft_clip || self.realign {
+            SOFT_CLIP_FLANK
+        } else {
+            0
+        }
+    }
+
+    /// Record passes read filters.
+    pub fn filter(&self, record: &Record) -> bool {
+        let flag = record.flag();
+        record.mapq() >= self.min_mapq
+            && flag.all_bits(self.include_flags)
+            && flag.no_bits(self.exclude_flags)
+    }
+}
+
+/// 1-based first and last aligned reference position of record, where soft clips start if any.
+fn aligned_span(record: &Record) -> (u32, u32) {
+    (record.start() as u32 + 1, record.calculate_end() as u32)
+}
+
+/// 1-based first and last reference position of record, soft clips taken as aligned.
+fn clipped_span(record: &Record) -> (u32, u32) {
+    let (first, last) = aligned_span(record);
+    let cigar = record.cigar();
+    (
+        first.saturating_sub(cigar.soft_clipping(true)).max(1),
+        last + cigar.soft_clipping(false),
+    )
+}
+
+/// Variant within `distance` bases of 1-based span ends.
+fn is_near_end(variant: &Variant, (first, last): (u32, u32), distance: u32) -> bool {
+    *variant.start() < first + distance || variant.end() + distance > last
+}
+
+/// 1-based position in read of variant start, counted from read 5' end, `None` if variant is not
+/// inside the aligned span of read.
+pub(crate) fn read_position(record: &Record, variant: &Variant) -> Option<u32> {
+    let (first, last) = aligned_span(record);
+    if *variant.start() < first || *variant.end() > last {
+        return None;
+    };
+    let mut pos = None;
+    for entry in record.alignment_entries().ok()? {
+        match entry.ref_pos() {
+            Some(v) if v + 1 > *variant.start() => break,
+            _ => pos = entry.record_pos().or(pos),
+        };
+    }
+    let pos = pos?;
+    if record.flag().is_reverse_strand() {
+        Some(record.sequence().len() as u32 - pos)
+    } else {
+        Some(pos + 1)
+    }
+}
+
+/// Read base of alignment entry is below min base quality.
+fn is_low_qual(record: &Record, entry: &AlignmentEntry, min_base_qual: u8) -> bool {
+    let quals = record.qualities();
+    quals.available()
+        && entry
+            .record_pos()
+            .is_some_and(|i| quals.raw()[i as usize] < min_base_qual)
 }
 
 fn logical_merge(e1: &Edit, e2: &Edit, merge: bool) -> bool {
@@ -34,136 +257,305 @@ fn logical_merge(e1: &Edit, e2: &Edit, merge: bool) -> bool {
 }
 
 pub trait VariantValidate {
-    fn validate(&self, variant: &Variant, option: &ValidateOptions) -> Option<bool>;
+    /// Reason of the allele of variant shown by record, with reference for evidence needing it.
+    fn reason_with_reference(
+        &self,
+        variant: &Variant,
+        option: &ValidateOptions,
+        reference: Option<&mut Reference>,
+    ) -> Result<Reason, Error>;
+
+    /// Reason of the allele of variant shown by record, without reference.
+    fn reason(&self, variant: &Variant, option: &ValidateOptions) -> Result<Reason, Error> {
+        self.reason_with_reference(variant, option, None)
+    }
+
+    /// Allele of variant shown by record, `None` if record is not informative of it.
+    fn validate(
+        &self,
+        variant: &Variant,
+        option: &ValidateOptions,
+    ) -> Result<Option<Allele>, Error> {
+        Ok(self.reason(variant, option)?.allele())
+    }
 }
 
 impl VariantValidate for Record {
-    /// Validate record supportion for variant.
+    /// Reason of the allele of variant shown by record, or of record not being informative of it.
     ///
     /// ## Examples
     ///
     /// ```rust
     /// use bam::record::Record;
-    /// use varlink::{Variant, VariantValidate, ValidateOptions};
+    /// use varlink::{Reason, Variant, VariantValidate, ValidateOptions};
     ///
     /// let var = Variant::from_hgvs("chr1:g.123456Adel").unwrap();
     /// let options = ValidateOptions::default();
     /// let record = Record::new();
     ///
-    /// assert!(record.validate(&var, &options).is_none());
+    /// assert_eq!(record.reason(&var, &options).unwrap(), Reason::NoSequence);
+    /// assert!(record.validate(&var, &options).unwrap().is_none());
     /// ```
     ///
+    /// ## Errors
+    ///
+    /// - [`Error::MissingMd`] if record covering variant has no `MD` tag.
+    /// - [`Error::ReferenceRequired`] if duplication or soft clip evidence is given without
+    ///   reference, duplication is filled by reference otherwise.
+    ///
     /// ## Warn
     ///
     /// Crate `bam` bam reader reading alignemnt with 0-based position, while variant is 1-based.
     /// So alignment `+1` or variant `-1` is necessary in some places.
-    #[allow(clippy::blocks_in_if_conditions)]
-    fn validate(&self, variant: &Variant, options: &ValidateOptions) -> Option<bool> {
-        // Unmapped read or out of region.
-        if (!self.flag().is_mapped())
-            || (self.start() + 1) as u32 > *variant.end()
+    fn reason_with_reference(
+        &self,
+        variant: &Variant,
+        options: &ValidateOptions,
+        mut reference: Option<&mut Reference>,
+    ) -> Result<Reason, Error> {
+        // Duplication validated as insertion of sequence filled by reference.
+        if variant.edit().is_dup() {
+            let variant = match reference.as_deref_mut() {
+                Some(r) => variant.clone().fill_dup(r)?,
+                None => {
+                    return Err(Error::ReferenceRequired(format!(
+                        "duplication at {}:{}-{}",
+                        variant.contig(),
+                        variant.start(),
+                        variant.end()
+                    )))
+                }
+            };
+            return self.reason_with_reference(&variant, options, reference);
+        };
+
+        // Unmapped, filtered read or read without sequence.
+        if !self.flag().is_mapped() {
+            return Ok(Reason::Unmapped);
+        } else if !options.filter(self) {
+            return Ok(Reason::Filtered);
+        } else if !self.sequence().available() {
+            return Ok(Reason::NoSequence);
+        };
+
+        // Deletion longer than read, supported by split alignments or discordant pair.
+        if variant.edit().is_del()
+            && !self.flag().is_supplementary()
+            && variant.affected_length() > self.sequence().len() as u32
+        {
+            if deletion::is_split(self, variant, options.min_mapq) {
+                return Ok(Reason::Split);
+            } else if deletion::is_discordant(self, variant, options.max_insert) {
+                return Ok(Reason::Discordant);
+            };
+        };
+
+        // Inversion supported by split alignments.
+        if variant.edit().is_inv() && inversion::is_split(self, variant) {
+            return Ok(Reason::Split);
+        };
+
+        // Variant in soft clip.
+        if options.soft_clip && !variant.edit().is_inv() {
+            let reference = reference
+                .as_deref_mut()
+                .ok_or_else(|| Error::ReferenceRequired("soft clip evidence".to_string()))?;
+            if let Some(v) = softclip::validate(self, variant, reference, options.min_base_qual)? {
+                // Near end of clip, where few bases are left to tell haplotypes apart.
+                if options.end_distance > 0
+                    && is_near_end(variant, clipped_span(self), options.end_distance)
+                {
+                    return Ok(Reason::NearEnd);
+                };
+                return Ok(v);
+            };
+        };
+
+        // Indel realigned to reference around read, soft clips included.
+        let is_indel =
+            variant.edit().is_ins() || variant.edit().is_del() || variant.edit().is_delins();
+        if options.realign && is_indel && !options.long_read {
+            if let Some(reference) = reference {
+                let span = clipped_span(self);
+                if span.0 > *variant.end() || span.1 < *variant.start() {
+                    return Ok(Reason::NotCovered);
+                } else if options.end_distance > 0
+                    && is_near_end(variant, span, options.end_distance)
+                {
+                    return Ok(Reason::NearEnd);
+                };
+                return realign::validate(self, variant, Some(reference), options.min_base_qual);
+            };
+        };
+
+        // Out of region.
+        if (self.start() + 1) as u32 > *variant.end()
             || (self.calculate_end() as u32) < *variant.start()
         {
-            return None;
+            return Ok(Reason::NotCovered);
         }
 
-        let mut iter = if let Ok(v) = self.alignment_entries() {
-            v.skip_while(|i| {
-                i.ref_pos() < Some(variant.start() - 1 - (!variant.edit().is_ins() as u32))
-            })
-        } else {
-            return None;
+        if self.alignment_entries().is_err() {
+            return Err(Error::MissingMd(
+                String::from_utf8_lossy(self.name()).into_owned(),
+            ));
         };
 
-        // If nt before start position is insertion, deletion or mismatch.
-        let mut next: Option<AlignmentEntry> = if let Some(v) = iter.next() {
-            Some(v)
-        } else {
-            return None;
-        };
-        if next.as_ref().map_or(false, |v| {
-            let curredit = if v.is_insertion() {
-                Edit::Ins
-            } else if v.is_deletion() {
-                Edit::Del
-            } else if !v.is_seq_match() {
-                Edit::Sub
-            } else {
-                Edit::Identity
-            };
-            logical_merge(&curredit, variant.edit(), options.merge)
-        }) {
-            return Some(false);
+        // Variant near read end, where alignment artifacts are common.
+        if options.end_distance > 0
+            && is_near_end(variant, aligned_span(self), options.end_distance)
+        {
+            return Ok(Reason::NearEnd);
         };
 
-        let refdef = if variant.edit().is_del() {
-            unsafe { String::from_utf8_unchecked(vec![b'N'; variant.affected_length() as usize]) }
-        } else {
-            String::from("")
+        if variant.edit().is_inv() {
+            return Ok(inversion::validate(self, variant, options.min_base_qual));
         };
 
-        let mut refseq = variant.refseq().unwrap_or(&refdef).as_bytes().iter();
-        let mut refnt = refseq.next();
-        let mut altseq = variant.altseq().unwrap_or("").as_bytes().iter();
-        let mut altnt = altseq.next();
+        if options.long_read {
+            return Ok(longread::validate(self, variant, options.min_base_qual));
+        };
 
-        // In case both ref and alt are empty.
-        if refnt.is_none() && altnt.is_none() && !variant.edit().is_del() {
-            return Some(true);
+        if options.realign && is_indel {
+            return realign::validate(self, variant, None, options.min_base_qual);
         };
 
-        next = iter.next();
-        loop {
-            let curr = match next {
-                Some(v) => v,
-                // Variant validated, but no more nt for next record position.
-                None => break Some(refnt.is_none() && altnt.is_none()),
-            };
-            let curredit = if curr.is_insertion() {
-                Edit::Ins
-            } else if curr.is_deletion() {
-                Edit::Del
-            } else if !curr.is_seq_match() {
-                Edit::Sub
-            } else {
-                Edit::Identity
-            };
+        Ok(validate_aligned(self, variant, options))
+    }
+}
 
-            if refnt.is_none()
-                && altnt.is_none()
-                && logical_merge(&curredit, variant.edit(), options.merge)
-            {
-                // Variant validated, but next record position is deletion, insertion or mismatch.
-                break Some(false);
-            } else if refnt.is_none() && altnt.is_none() {
-                // Variant validated.
-                break Some(true);
-            };
+/// Validate record by its aligned entries.
+#[allow(clippy::blocks_in_conditions)]
+fn validate_aligned(record: &Record, variant: &Variant, options: &ValidateOptions) -> Reason {
+    let mut iter = if let Ok(v) = record.alignment_entries() {
+        v.skip_while(|i| {
+            i.ref_pos() < Some(variant.start() - 1 - (!variant.edit().is_ins() as u32))
+        })
+    } else {
+        return Reason::NotCovered;
+    };
+
+    // If nt before start position is insertion, deletion or mismatch.
+    let mut next: Option<AlignmentEntry> = if let Some(v) = iter.next() {
+        Some(v)
+    } else {
+        return Reason::TooShort;
+    };
+    if next.as_ref().is_some_and(|v| {
+        let curredit = if v.is_insertion() {
+            Edit::Ins
+        } else if v.is_deletion() {
+            Edit::Del
+        } else if !v.is_seq_match() {
+            Edit::Sub
+        } else {
+            Edit::Identity
+        };
+        logical_merge(&curredit, variant.edit(), options.merge)
+    }) {
+        if next
+            .as_ref()
+            .is_some_and(|v| is_low_qual(record, v, options.min_base_qual))
+        {
+            // Read base not reliable to reject variant.
+            return Reason::LowQuality;
+        };
+        return Reason::FlankMismatch;
+    };
+
+    let refdef = if variant.edit().is_del() {
+        unsafe { String::from_utf8_unchecked(vec![b'N'; variant.affected_length() as usize]) }
+    } else {
+        String::from("")
+    };
 
-            next = iter.next();
+    let mut refseq = variant.refseq().unwrap_or(&refdef).as_bytes().iter();
+    let mut refnt = refseq.next();
+    let mut altseq = variant.altseq().unwrap_or("").as_bytes().iter();
+    let mut altnt = altseq.next();
 
-            if curr.is_insertion() && curr.record_nt().as_ref() == altnt {
-                // Insertion consumes one variant alt nt.
-                altnt = altseq.next();
-            } else if curr.is_insertion() {
-                break Some(false);
-            } else if curr.is_deletion() && curr.ref_nt().is_some() {
-                // Deletion consumes one variant ref nt.
+    // In case both ref and alt are empty.
+    if refnt.is_none() && altnt.is_none() && !variant.edit().is_del() {
+        return Reason::Supported;
+    };
+
+    // Any variant nt consumed by insertion, deletion or mismatch.
+    let mut started = false;
+    next = iter.next();
+    loop {
+        let curr = match next {
+            Some(v) => v,
+            // Variant validated, but no more nt for next record position.
+            None if refnt.is_none() && altnt.is_none() => break Reason::Supported,
+            // Record ends within variant.
+            None if started => break Reason::Partial,
+            None => break Reason::TooShort,
+        };
+        let curredit = if curr.is_insertion() {
+            Edit::Ins
+        } else if curr.is_deletion() {
+            Edit::Del
+        } else if !curr.is_seq_match() {
+            Edit::Sub
+        } else {
+            Edit::Identity
+        };
+
+        if refnt.is_none()
+            && altnt.is_none()
+            && logical_merge(&curredit, variant.edit(), options.merge)
+            && is_low_qual(record, &curr, options.min_base_qual)
+        {
+            // Read base not reliable to reject variant.
+            break Reason::LowQuality;
+        } else if refnt.is_none()
+            && altnt.is_none()
+            && logical_merge(&curredit, variant.edit(), options.merge)
+        {
+            // Variant validated, but next record position is deletion, insertion or mismatch.
+            break Reason::MergedNeighbor;
+        } else if refnt.is_none() && altnt.is_none() {
+            // Variant validated.
+            break Reason::Supported;
+        } else if is_low_qual(record, &curr, options.min_base_qual) {
+            // Read base not reliable to confirm or reject variant.
+            break Reason::LowQuality;
+        };
+
+        next = iter.next();
+
+        if curr.is_insertion() && curr.record_nt().as_ref() == altnt {
+            // Insertion consumes one variant alt nt.
+            altnt = altseq.next();
+            started = true;
+        } else if curr.is_insertion() {
+            break Reason::WrongInsertion;
+        } else if curr.is_deletion() && curr.ref_nt().is_some() {
+            // Deletion consumes one variant ref nt.
+            refnt = refseq.next();
+            started = true;
+        } else if curr.is_deletion() {
+            break Reason::OtherIndel;
+        } else if curr.record_nt().as_ref() == altnt {
+            // 1. Match or mismatch consumes a variant ref and alt pair nts.
+            // 2. Delins with ref sequence, comsume a variant ref and alt pair nts
+            //  if record ref nt == variant ref nt, otherwise consume only a variant alt nt.
+            // 3. Delins without ref sequence, consume a variant alt.
+            if refnt.is_some() && curr.ref_nt().as_ref() == refnt {
                 refnt = refseq.next();
-            } else if curr.is_deletion() {
-                break Some(false);
-            } else if curr.record_nt().as_ref() == altnt {
-                // 1. Match or mismatch consumes a variant ref and alt pair nts.
-                // 2. Delins with ref sequence, comsume a variant ref and alt pair nts
-                //  if record ref nt == variant ref nt, otherwise consume only a variant alt nt.
-                // 3. Delins without ref sequence, consume a variant alt.
-                if refnt.is_some() && curr.ref_nt().as_ref() == refnt {
-                    refnt = refseq.next();
-                };
-                altnt = altseq.next();
-            } else {
-                break Some(false);
             };
-        }
+            altnt = altseq.next();
+            started |= !curr.is_seq_match();
+        } else if !started && curr.is_seq_match() {
+            // Reference shown before any variant nt.
+            break Reason::Reference;
+        } else if started
+            || (record.calculate_end() as u32) < *variant.end() + variant.edit().is_del() as u32
+        {
+            // Record ends within variant, or before base after deletion.
+            break Reason::Partial;
+        } else {
+            break Reason::Mismatch;
+        };
     }
 }
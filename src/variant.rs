@@ -1,6 +1,11 @@
-use std::io;
+use std::fmt::{self, Display};
 use std::str::FromStr;
 
+use serde::Serialize;
+
+use crate::inversion::reverse_complement;
+use crate::{Error, Reference};
+
 mod noms {
     pub(super) use nom::bytes::complete::{is_a, tag, take_until};
     pub(super) use nom::character::complete::digit1;
@@ -10,7 +15,8 @@ mod noms {
     pub(super) use nom::{Err, IResult};
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Edit {
     // Substitution
     Sub,
@@ -22,6 +28,10 @@ pub enum Edit {
     Delins,
     // Identity
     Identity,
+    // Duplication with sequence not known yet, insertion once filled by reference
+    Dup,
+    // Inversion
+    Inv,
 }
 
 #[derive(Debug, PartialEq)]
@@ -31,13 +41,41 @@ pub enum Format {
 }
 
 impl FromStr for Format {
-    type Err = io::Error;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_ascii_lowercase().as_str() {
             "hgvs" => Ok(Self::Hgvs),
             "vcf" => Ok(Self::Vcf),
-            _ => Err(io::Error::new(io::ErrorKind::InvalidData, s)),
+            _ => Err(Error::Parse {
+                input: s.to_string(),
+                offset: 0,
+            }),
+        }
+    }
+}
+
+/// Rule shifting indels in repeats to a canonical position.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalization {
+    // Leftmost position, as vcf.
+    Left,
+    // Rightmost position, as hgvs 3' rule.
+    Right,
+}
+
+impl FromStr for Normalization {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" | "vcf" => Ok(Self::Left),
+            "right" | "hgvs" => Ok(Self::Right),
+            _ => Err(Error::Parse {
+                input: s.to_string(),
+                offset: 0,
+            }),
         }
     }
 }
@@ -62,9 +100,53 @@ impl Edit {
     pub fn is_identity(&self) -> bool {
         matches!(self, &Self::Identity)
     }
+
+    pub fn is_dup(&self) -> bool {
+        matches!(self, &Self::Dup)
+    }
+
+    pub fn is_inv(&self) -> bool {
+        matches!(self, &Self::Inv)
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// Edit names, as serialized.
+impl Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Sub => "sub",
+            Self::Del => "del",
+            Self::Ins => "ins",
+            Self::Delins => "delins",
+            Self::Identity => "identity",
+            Self::Dup => "dup",
+            Self::Inv => "inv",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Edit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sub" => Ok(Self::Sub),
+            "del" => Ok(Self::Del),
+            "ins" => Ok(Self::Ins),
+            "delins" => Ok(Self::Delins),
+            "identity" => Ok(Self::Identity),
+            "dup" => Ok(Self::Dup),
+            "inv" => Ok(Self::Inv),
+            _ => Err(Error::Parse {
+                input: s.to_string(),
+                offset: 0,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 pub struct Variant {
     contig: String,
     start: u32,
@@ -103,6 +185,10 @@ fn parse_edit(input: &str) -> noms::IResult<&str, Edit> {
     if edit.is_some() {
         return Ok((res, Edit::Sub));
     }
+    let (res, edit) = noms::opt(noms::tag("="))(input)?;
+    if edit.is_some() {
+        return Ok((res, Edit::Identity));
+    }
     let (res, edit) = noms::opt(noms::tag("ins"))(input)?;
     if edit.is_some() {
         return Ok((res, Edit::Ins));
@@ -115,12 +201,26 @@ fn parse_edit(input: &str) -> noms::IResult<&str, Edit> {
     if edit.is_some() {
         return Ok((res, Edit::Del));
     }
+    let (res, edit) = noms::opt(noms::tag("dup"))(input)?;
+    if edit.is_some() {
+        return Ok((res, Edit::Dup));
+    }
+    let (res, edit) = noms::opt(noms::tag("inv"))(input)?;
+    if edit.is_some() {
+        return Ok((res, Edit::Inv));
+    }
     Err(noms::Err::Error(noms::Error::new(
         res,
         noms::ErrorKind::TagBits,
     )))
 }
 
+/// Vcf alt allele not describing sequence, spanning deletion `*`, symbolic as `<NON_REF>`,
+/// breakend or missing `.`.
+pub(crate) fn is_skipped_allele(allele: &str) -> bool {
+    allele == "*" || allele == "." || allele.starts_with('<') || allele.contains(&['[', ']'][..])
+}
+
 impl Variant {
     pub fn contig(&self) -> &str {
         &self.contig
@@ -149,7 +249,7 @@ impl Variant {
     /// Length affected.
     pub fn affected_length(&self) -> u32 {
         match *self.edit() {
-            Edit::Del => self.end - self.start + 1,
+            Edit::Del | Edit::Dup | Edit::Inv => (self.end + 1).saturating_sub(self.start),
             Edit::Ins => self.altseq().map_or(0, |v| v.len() as u32),
             Edit::Sub => 1,
             Edit::Delins => u32::max(
@@ -176,7 +276,11 @@ impl Variant {
     /// assert_eq!(var.refseq(), Some("A"));
     /// assert_eq!(var.altseq(), Some("G"));
     /// ```
-    pub fn from_hgvs(input: &str) -> Result<Self, noms::Err<noms::Error<&str>>> {
+    pub fn from_hgvs(input: &str) -> Result<Self, Error> {
+        Self::parse_hgvs(input).map_err(|e| Error::parse(input, e))
+    }
+
+    fn parse_hgvs(input: &str) -> Result<Self, noms::Err<noms::Error<&str>>> {
         // Parse contig
         let (res, contig) = parse_contig(input)?;
         let (res, _) = noms::tag(":")(res)?;
@@ -184,12 +288,55 @@ impl Variant {
         let (res, _) = noms::opt(noms::tag("g."))(res)?;
         // Parse position
         let (res, (start, end)) = parse_position(res)?;
+        Self::parse_change(contig, start, end, res, false)
+    }
+
+    /// Parse sequence change of `start..=end` following hgvs position, with sequences reverse
+    /// complemented if given on reverse strand.
+    pub(crate) fn parse_change(
+        contig: String,
+        start: u32,
+        end: u32,
+        input: &str,
+        reverse: bool,
+    ) -> Result<Self, noms::Err<noms::Error<&str>>> {
+        // Range given backwards, as `12347_12345`.
+        if end < start {
+            return Err(noms::Err::Error(noms::Error::new(
+                input,
+                noms::ErrorKind::Verify,
+            )));
+        };
         // Parse ref sequence
-        let (res, refseq) = noms::opt(parse_sequence)(res)?;
+        let (res, refseq) = noms::opt(parse_sequence)(input)?;
         // Parse edit
         let (res, edit) = parse_edit(res)?;
         // Parse alt sequence
-        let (res, altseq) = noms::opt(parse_sequence)(res)?;
+        let (mut res, mut altseq) = noms::opt(parse_sequence)(res)?;
+        let (mut edit, mut refseq) = (edit, refseq);
+        if edit.is_del() {
+            // Deleted sequence, and deletion-insertion as `delAGinsT`.
+            refseq = refseq.or_else(|| altseq.take());
+            let (rest, ins) = noms::opt(noms::tuple((noms::tag("ins"), parse_sequence)))(res)?;
+            if let Some((_, v)) = ins {
+                edit = Edit::Delins;
+                altseq = Some(v);
+                res = rest;
+            };
+        };
+        if edit.is_identity() {
+            altseq = refseq.clone();
+        };
+        let (refseq, altseq) = if reverse {
+            let revcomp =
+                |v: String| String::from_utf8_lossy(&reverse_complement(v.as_bytes())).into_owned();
+            (refseq.map(revcomp), altseq.map(revcomp))
+        } else {
+            (refseq, altseq)
+        };
+        if edit.is_dup() {
+            return Self::dup(contig, start, end, altseq.or(refseq), res);
+        };
         let var = Variant {
             contig,
             start,
@@ -198,7 +345,10 @@ impl Variant {
             refseq,
             altseq,
         };
-        if !matches!(var.edit, Edit::Del) && var.refseq.is_none() && var.altseq.is_none() {
+        if !matches!(var.edit, Edit::Del | Edit::Inv)
+            && var.refseq.is_none()
+            && var.altseq.is_none()
+        {
             return Err(noms::Err::Error(noms::Error::new(
                 res,
                 noms::ErrorKind::Verify,
@@ -213,6 +363,76 @@ impl Variant {
         Ok(var)
     }
 
+    /// Duplication of `start..=end`, insertion of duplicated sequence after `end` if given.
+    fn dup(
+        contig: String,
+        start: u32,
+        end: u32,
+        seq: Option<String>,
+        res: &str,
+    ) -> Result<Self, noms::Err<noms::Error<&str>>> {
+        if seq
+            .as_ref()
+            .is_some_and(|v| v.len() as u32 != end - start + 1)
+        {
+            return Err(noms::Err::Error(noms::Error::new(
+                res,
+                noms::ErrorKind::Verify,
+            )));
+        };
+        if !res.is_empty() {
+            return Err(noms::Err::Error(noms::Error::new(
+                res,
+                noms::ErrorKind::NonEmpty,
+            )));
+        };
+        let var = match seq {
+            Some(seq) => Variant {
+                contig,
+                start: end,
+                end: end + 1,
+                edit: Edit::Ins,
+                refseq: None,
+                altseq: Some(seq),
+            },
+            None => Variant {
+                contig,
+                start,
+                end,
+                edit: Edit::Dup,
+                refseq: None,
+                altseq: None,
+            },
+        };
+        Ok(var)
+    }
+
+    /// Fill duplicated sequence by reference, turning duplication into insertion.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::{Edit, Reference, Variant};
+    ///
+    /// // chrT:10-12 is GCT.
+    /// let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    /// let var = Variant::from_hgvs("chrT:g.10_12dup").unwrap();
+    /// assert_eq!(var.edit(), &Edit::Dup);
+    /// let var = var.fill_dup(&mut reference).unwrap();
+    /// assert_eq!(var, Variant::from_hgvs("chrT:12_13insGCT").unwrap());
+    /// assert_eq!(var, Variant::from_hgvs("chrT:10_12dupGCT").unwrap());
+    /// ```
+    pub fn fill_dup(mut self, reference: &mut Reference) -> Result<Self, Error> {
+        if self.edit.is_dup() {
+            let seq = reference.fetch(&self.contig, self.start, self.end)?;
+            self.altseq = Some(String::from_utf8_lossy(&seq).into_owned());
+            self.edit = Edit::Ins;
+            self.start = self.end;
+            self.end += 1;
+        };
+        Ok(self)
+    }
+
     /// Parse vcf string into variant
     ///
     /// Vcf type variant comes in formats:
@@ -244,48 +464,77 @@ impl Variant {
     /// assert_eq!(var.refseq(), Some("AGC"));
     /// assert_eq!(var.altseq(), Some("T"));
     /// ```
-    pub fn from_vcf(input: &str) -> Result<Self, noms::Err<noms::Error<&str>>> {
+    pub fn from_vcf(input: &str) -> Result<Self, Error> {
+        Self::parse_vcf(input).map_err(|e| Error::parse(input, e))
+    }
+
+    fn parse_vcf(input: &str) -> Result<Self, noms::Err<noms::Error<&str>>> {
         // contig
         let (res, contig) = parse_contig(input)?;
         let (res, _) = noms::tag(":")(res)?;
         // position
-        let (res, mut start) = parse_position1(res)?;
+        let (res, start) = parse_position1(res)?;
         // Parse ref sequence
-        let (res, mut refseq) = parse_sequence(res)?;
+        let (res, refseq) = parse_sequence(res)?;
         let (res, _) = noms::tag(">")(res)?;
         // Parse alt sequence
-        let (res, mut altseq) = parse_sequence(res)?;
+        let (res, altseq) = parse_sequence(res)?;
         if !res.is_empty() {
             return Err(noms::Err::Error(noms::Error::new(
                 res,
                 noms::ErrorKind::NonEmpty,
             )));
         };
-        let var = if refseq == altseq {
+        Ok(Self::from_alleles(contig, start, refseq, altseq))
+    }
+
+    /// Variant of vcf ref and alt alleles, both not empty and starting at `start`.
+    ///
+    /// Common suffix, then common prefix of alleles is trimmed, as alt alleles split from
+    /// multi-allelic record share the longest ref allele.
+    fn from_alleles(contig: String, mut start: u32, refseq: String, altseq: String) -> Self {
+        if refseq == altseq {
             // Identity
-            Variant {
+            return Variant {
                 contig,
                 start,
                 end: start + refseq.len() as u32 - 1,
                 edit: Edit::Identity,
                 refseq: Some(refseq),
                 altseq: Some(altseq),
-            }
-        } else if refseq.len() == 1 && altseq.len() == 1 {
-            // Sub
+            };
+        };
+        let (mut refseq, mut altseq) = (refseq.as_str(), altseq.as_str());
+        // Suffix, keeping at least one base of both.
+        while refseq.len() > 1
+            && altseq.len() > 1
+            && refseq.as_bytes().last() == altseq.as_bytes().last()
+        {
+            refseq = &refseq[..refseq.len() - 1];
+            altseq = &altseq[..altseq.len() - 1];
+        }
+        // Prefix
+        while !refseq.is_empty()
+            && !altseq.is_empty()
+            && refseq.as_bytes()[0] == altseq.as_bytes()[0]
+        {
+            refseq = &refseq[1..];
+            altseq = &altseq[1..];
+            start += 1;
+        }
+        let (refseq, altseq) = (refseq.to_string(), altseq.to_string());
+        if refseq.is_empty() {
+            // Insertion, between the last trimmed base and `start`.
             Variant {
                 contig,
-                start,
+                start: start - 1,
                 end: start,
-                edit: Edit::Sub,
-                refseq: Some(refseq),
+                edit: Edit::Ins,
+                refseq: None,
                 altseq: Some(altseq),
             }
-        } else if refseq.starts_with(&altseq) {
+        } else if altseq.is_empty() {
             // Deletion
-            let len = altseq.len();
-            let refseq = refseq.split_off(len);
-            start += len as u32;
             let end = start + refseq.len() as u32 - 1;
             Variant {
                 contig,
@@ -295,17 +544,14 @@ impl Variant {
                 refseq: Some(refseq),
                 altseq: None,
             }
-        } else if altseq.starts_with(&refseq) {
-            // Insertion
-            let len = refseq.len();
-            let altseq = altseq.split_off(len);
-            let end = start + 1;
+        } else if refseq.len() == 1 && altseq.len() == 1 {
+            // Sub
             Variant {
                 contig,
                 start,
-                end,
-                edit: Edit::Ins,
-                refseq: None,
+                end: start,
+                edit: Edit::Sub,
+                refseq: Some(refseq),
                 altseq: Some(altseq),
             }
         } else {
@@ -318,8 +564,272 @@ impl Variant {
                 refseq: Some(refseq),
                 altseq: Some(altseq),
             }
+        }
+    }
+
+    /// Parse tab separated vcf data line into variants, one for each alt allele. Spanning
+    /// deletion `*`, symbolic alleles as `<NON_REF>`, breakends and missing `.` are skipped.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::Variant;
+    ///
+    /// let vars = Variant::from_vcf_line("1\t12345\trs1\tAG\tA,T,*,<NON_REF>\t50\tPASS\t.").unwrap();
+    /// assert_eq!(vars.len(), 2);
+    /// assert_eq!(vars[0], Variant::from_vcf("1:12345AG>A").unwrap());
+    /// assert_eq!(vars[1], Variant::from_vcf("1:12345AG>T").unwrap());
+    /// ```
+    pub fn from_vcf_line(input: &str) -> Result<Vec<Self>, Error> {
+        let line = input.trim_end_matches(&['\r', '\n'][..]);
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        for field in line.split('\t').take(5) {
+            fields.push((offset, field));
+            offset += field.len() + 1;
+        }
+        let invalid = |offset| Error::Parse {
+            input: input.to_string(),
+            offset,
         };
-        Ok(var)
+        if fields.len() < 5 {
+            // Too few columns.
+            return Err(invalid(line.len()));
+        };
+        let is_sequence = |v: &str| {
+            !v.is_empty()
+                && v.bytes()
+                    .all(|b| matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'N'))
+        };
+        let start = match fields[1].1.parse::<u32>() {
+            Ok(v) if v > 0 => v,
+            _ => return Err(invalid(fields[1].0)),
+        };
+        let (offset, refseq) = fields[3];
+        if !is_sequence(refseq) {
+            return Err(invalid(offset));
+        };
+        let mut variants = Vec::new();
+        let mut offset = fields[4].0;
+        for altseq in fields[4].1.split(',') {
+            if !is_skipped_allele(altseq) {
+                if !is_sequence(altseq) {
+                    return Err(invalid(offset));
+                };
+                variants.push(Self::from_alleles(
+                    fields[0].1.to_string(),
+                    start,
+                    refseq.to_ascii_uppercase(),
+                    altseq.to_ascii_uppercase(),
+                ));
+            };
+            offset += altseq.len() + 1;
+        }
+        Ok(variants)
+    }
+
+    /// Shift deletion or insertion in repeats to canonical position by reference, and fill
+    /// deleted sequence. Reference sequence given by variant is checked against reference.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::{Normalization, Reference, Variant};
+    ///
+    /// // chrT:5-9 is AAAAA.
+    /// let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    /// let var = Variant::from_hgvs("chrT:7del").unwrap();
+    /// let left = var.normalize(&mut reference, Normalization::Left).unwrap();
+    /// assert_eq!((left.start(), left.end(), left.refseq()), (&5, &5, Some("A")));
+    /// // Vcf and hgvs style of the same deletion.
+    /// let vcf = Variant::from_vcf("chrT:4TA>T").unwrap();
+    /// let hgvs = Variant::from_hgvs("chrT:9del").unwrap();
+    /// assert_eq!(
+    ///     vcf.normalize(&mut reference, Normalization::Right).unwrap(),
+    ///     hgvs.normalize(&mut reference, Normalization::Right).unwrap()
+    /// );
+    /// ```
+    pub fn normalize(
+        mut self,
+        reference: &mut Reference,
+        rule: Normalization,
+    ) -> Result<Self, Error> {
+        self = self.fill_dup(reference)?;
+        let contig_len = reference
+            .contig_len(&self.contig)
+            .ok_or_else(|| Error::UnknownContig(self.contig.clone()))?;
+        if !self.edit.is_ins() {
+            let refseq = reference.fetch(&self.contig, self.start, self.end)?;
+            let refseq = String::from_utf8_lossy(&refseq).into_owned();
+            if !self.edit.is_del() && self.refseq.is_none() {
+                return Ok(self);
+            };
+            if self
+                .refseq
+                .as_ref()
+                .is_some_and(|v| !v.eq_ignore_ascii_case(&refseq))
+            {
+                return Err(Error::RefMismatch {
+                    contig: self.contig,
+                    start: self.start,
+                    expected: refseq,
+                });
+            };
+            self.refseq = Some(refseq);
+        };
+        match (&self.edit, rule) {
+            (Edit::Del, Normalization::Left) => {
+                while self.start > 1
+                    && reference.base(&self.contig, self.start - 1)?
+                        == reference.base(&self.contig, self.end)?
+                {
+                    self.start -= 1;
+                    self.end -= 1;
+                }
+            }
+            (Edit::Del, Normalization::Right) => {
+                while self.end < contig_len
+                    && reference.base(&self.contig, self.end + 1)?
+                        == reference.base(&self.contig, self.start)?
+                {
+                    self.start += 1;
+                    self.end += 1;
+                }
+            }
+            (Edit::Ins, Normalization::Left) => {
+                let mut altseq = self.altseq.take().unwrap_or_default().into_bytes();
+                while self.start >= 1
+                    && altseq.last() == Some(&reference.base(&self.contig, self.start)?)
+                {
+                    altseq.rotate_right(1);
+                    self.start -= 1;
+                    self.end -= 1;
+                }
+                self.altseq = Some(String::from_utf8_lossy(&altseq).into_owned());
+            }
+            (Edit::Ins, Normalization::Right) => {
+                let mut altseq = self.altseq.take().unwrap_or_default().into_bytes();
+                while self.end <= contig_len
+                    && altseq.first() == Some(&reference.base(&self.contig, self.end)?)
+                {
+                    altseq.rotate_left(1);
+                    self.start += 1;
+                    self.end += 1;
+                }
+                self.altseq = Some(String::from_utf8_lossy(&altseq).into_owned());
+            }
+            _ => return Ok(self),
+        };
+        if self.edit.is_del() {
+            let refseq = reference.fetch(&self.contig, self.start, self.end)?;
+            self.refseq = Some(String::from_utf8_lossy(&refseq).into_owned());
+        };
+        Ok(self)
+    }
+
+    /// Hgvs string of variant, `g.` positions with deleted sequence if known.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::Variant;
+    ///
+    /// let var = Variant::from_vcf("1:12345AG>A").unwrap();
+    /// assert_eq!(var.to_hgvs(), "1:g.12346delG");
+    /// assert_eq!(var.to_hgvs().parse::<Variant>().unwrap(), var);
+    /// ```
+    pub fn to_hgvs(&self) -> String {
+        let pos = if self.start == self.end && !self.edit.is_ins() {
+            self.start.to_string()
+        } else {
+            format!("{}_{}", self.start, self.end)
+        };
+        let refseq = self.refseq().unwrap_or_default();
+        let altseq = self.altseq().unwrap_or_default();
+        let change = match self.edit {
+            Edit::Sub => format!("{}>{}", refseq, altseq),
+            Edit::Del => format!("del{}", refseq),
+            Edit::Ins => format!("ins{}", altseq),
+            Edit::Delins if refseq.is_empty() => format!("delins{}", altseq),
+            Edit::Delins => format!("del{}ins{}", refseq, altseq),
+            Edit::Identity => format!("{}=", refseq),
+            Edit::Dup => String::from("dup"),
+            Edit::Inv => String::from("inv"),
+        };
+        format!("{}:g.{}{}", self.contig, pos, change)
+    }
+
+    /// Vcf string of variant as `1:12345A>G`, anchor base of deletion and insertion taken from
+    /// reference, or `N` without reference. Reference is required for sequence not given by
+    /// variant, and inversion is written as deletion-insertion.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use varlink::{Reference, Variant};
+    ///
+    /// // chrT:4-6 is TAA.
+    /// let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    /// let var = Variant::from_hgvs("chrT:5_6del").unwrap();
+    /// assert_eq!(var.to_vcf(Some(&mut reference)).unwrap(), "chrT:4TAA>T");
+    /// assert!(var.to_vcf(None).is_err());
+    /// let var = Variant::from_vcf("chrT:4TAA>T").unwrap();
+    /// assert_eq!(var.to_vcf(None).unwrap(), "chrT:4NAA>N");
+    /// assert_eq!(Variant::from_vcf(&var.to_vcf(None).unwrap()).unwrap(), var);
+    /// ```
+    pub fn to_vcf(&self, mut reference: Option<&mut Reference>) -> Result<String, Error> {
+        if self.edit.is_dup() {
+            return match reference {
+                Some(r) => self.clone().fill_dup(r)?.to_vcf(Some(r)),
+                None => Err(Error::ReferenceRequired(format!("vcf of {}", self))),
+            };
+        };
+        let mut fetch = |start: u32, end: u32| -> Result<Option<String>, Error> {
+            match reference.as_mut() {
+                Some(r) => {
+                    let seq = r.fetch(&self.contig, start, end)?;
+                    Ok(Some(String::from_utf8_lossy(&seq).into_owned()))
+                }
+                None => Ok(None),
+            }
+        };
+        let refseq = match self.refseq.clone() {
+            Some(v) => Some(v),
+            None if self.edit.is_ins() => None,
+            None => fetch(self.start, self.end)?,
+        };
+        let refseq = || {
+            refseq
+                .clone()
+                .ok_or_else(|| Error::ReferenceRequired(format!("vcf of {}", self)))
+        };
+        let altseq = self.altseq().unwrap_or_default();
+        let (pos, refseq, altseq) = match self.edit {
+            Edit::Del if self.start > 1 => {
+                let anchor = fetch(self.start - 1, self.start - 1)?.unwrap_or_else(|| "N".into());
+                (self.start - 1, anchor.clone() + &refseq()?, anchor)
+            }
+            Edit::Del => {
+                let anchor = fetch(self.end + 1, self.end + 1)?.unwrap_or_else(|| "N".into());
+                (self.start, refseq()? + &anchor, anchor)
+            }
+            Edit::Ins => {
+                let anchor = fetch(self.start, self.start)?.unwrap_or_else(|| "N".into());
+                (self.start, anchor.clone(), anchor + altseq)
+            }
+            Edit::Inv => {
+                let refseq = refseq()?;
+                let altseq = reverse_complement(refseq.as_bytes());
+                (
+                    self.start,
+                    refseq,
+                    String::from_utf8_lossy(&altseq).into_owned(),
+                )
+            }
+            Edit::Identity => (self.start, refseq()?, refseq()?),
+            _ => (self.start, refseq()?, altseq.to_string()),
+        };
+        Ok(format!("{}:{}{}>{}", self.contig, pos, refseq, altseq))
     }
 
     /// Parse string to variant based on format given.
@@ -338,7 +848,7 @@ impl Variant {
     ///     (var4.contig(), var4.start(), var4.end(), var4.edit())
     /// );
     /// ```
-    pub fn from<'a>(input: &'a str, fmt: &Format) -> Result<Self, noms::Err<noms::Error<&'a str>>> {
+    pub fn from(input: &str, fmt: &Format) -> Result<Self, Error> {
         match fmt {
             Format::Hgvs => Self::from_hgvs(input),
             Format::Vcf => Self::from_vcf(input),
@@ -346,10 +856,137 @@ impl Variant {
     }
 }
 
+/// Hgvs string of variant.
+impl Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hgvs())
+    }
+}
+
+/// Parse hgvs string.
+impl FromStr for Variant {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hgvs(s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_dup() {
+        let var = Variant::from_hgvs("1:g.12345_12347dupAGC").unwrap();
+        assert_eq!(var, Variant::from_hgvs("1:12347_12348insAGC").unwrap());
+        let var = Variant::from_hgvs("1:12345dup").unwrap();
+        assert_eq!(
+            (var.start(), var.end(), var.edit()),
+            (&12345, &12345, &Edit::Dup)
+        );
+        assert_eq!(var.affected_length(), 1);
+        // Sequence length differs from duplicated range.
+        assert!(Variant::from_hgvs("1:12345_12347dupAG").is_err());
+        assert!(Variant::from_hgvs("1:12345_12347dupx").is_err());
+        // Range given backwards.
+        assert!(matches!(
+            Variant::from_hgvs("1:144852547_144852545dupAAA"),
+            Err(Error::Parse { offset: 21, .. })
+        ));
+        assert!(Variant::from_hgvs("1:144852547_144852545del").is_err());
+    }
+
+    #[test]
+    fn test_parse_inv() {
+        let var = Variant::from_hgvs("1:g.12345_12360inv").unwrap();
+        assert_eq!(
+            (var.start(), var.end(), var.edit()),
+            (&12345, &12360, &Edit::Inv)
+        );
+        assert_eq!((var.refseq(), var.altseq()), (None, None));
+        assert_eq!(var.affected_length(), 16);
+        assert!(Variant::from_hgvs("1:144852547_144852545inv").is_err());
+    }
+
+    #[test]
+    fn test_parse_vcf_line() {
+        let vars = Variant::from_vcf_line(
+            "1\t144852532\t.\tg\tGCCC,<NON_REF>\t967.60\t.\tAC=1\tGT\t0/1\n",
+        )
+        .unwrap();
+        assert_eq!(vars, vec![Variant::from_vcf("1:144852532G>GCCC").unwrap()]);
+        // Alt alleles sharing padded ref.
+        let vars = Variant::from_vcf_line("1\t144852540\t.\tAG\tA,AGT,TG").unwrap();
+        let hgvs: Vec<String> = vars.iter().map(|v| v.to_hgvs()).collect();
+        assert_eq!(
+            hgvs,
+            vec![
+                "1:g.144852541delG",
+                "1:g.144852541_144852542insT",
+                "1:g.144852540A>T"
+            ]
+        );
+        let vars = Variant::from_vcf_line("1\t100\t.\tA\t*,G]2:200]").unwrap();
+        assert!(vars.is_empty());
+        assert!(matches!(
+            Variant::from_vcf_line("1\t100\t.\tA\tG,AX"),
+            Err(Error::Parse { offset: 12, .. })
+        ));
+        assert!(matches!(
+            Variant::from_vcf_line("1\t0\t.\tA\tG"),
+            Err(Error::Parse { offset: 2, .. })
+        ));
+        assert!(matches!(
+            Variant::from_vcf_line("1\t100\t.\tA"),
+            Err(Error::Parse { offset: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+        for hgvs in &[
+            "chrT:g.10G>A",
+            "chrT:g.5_6delAA",
+            "chrT:g.5_6del",
+            "chrT:g.12_13insGG",
+            "chrT:g.10_11delGCinsT",
+            "chrT:g.10_11delinsT",
+            "chrT:g.10_11GC=",
+            "chrT:g.10_12dup",
+            "chrT:g.19_24inv",
+        ] {
+            let var: Variant = hgvs.parse().unwrap();
+            assert_eq!(&var.to_string(), hgvs);
+            let vcf = var.to_vcf(Some(&mut reference)).unwrap();
+            let var = Variant::from_vcf(&vcf).unwrap();
+            assert_eq!(var.to_vcf(Some(&mut reference)).unwrap(), vcf);
+            assert_eq!(var.to_hgvs().parse::<Variant>().unwrap(), var);
+        }
+        assert_eq!(
+            Variant::from_hgvs("chrT:1del")
+                .unwrap()
+                .to_vcf(Some(&mut reference))
+                .unwrap(),
+            "chrT:1AC>C"
+        );
+        assert_eq!("delins".parse::<Edit>().unwrap(), Edit::Delins);
+        assert_eq!(Edit::Delins.to_string(), "delins");
+    }
+
+    #[test]
+    fn test_parse_error_offset() {
+        assert!(matches!(
+            Variant::from_hgvs("1:12345A>Gfoo"),
+            Err(Error::Parse { offset: 10, .. })
+        ));
+        assert!(matches!(
+            Variant::from_vcf("1:x12345A>G"),
+            Err(Error::Parse { offset: 2, .. })
+        ));
+    }
+
     #[test]
     fn test_parse_position() {
         let pos_str = "12345_12346del";
@@ -396,6 +1033,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalize_insertion() {
+        // chrT:13-18 is CACACA.
+        let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+        let var = Variant::from_hgvs("chrT:14_15insCA").unwrap();
+        let var = var.normalize(&mut reference, Normalization::Right).unwrap();
+        assert_eq!((var.start, var.end), (18, 19));
+        assert_eq!(var.altseq(), Some("CA"));
+        let var = var.normalize(&mut reference, Normalization::Left).unwrap();
+        assert_eq!((var.start, var.end), (12, 13));
+        assert_eq!(var.altseq(), Some("CA"));
+        let var = Variant::from_hgvs("chrT:10_11insT").unwrap();
+        let var = var.normalize(&mut reference, Normalization::Left).unwrap();
+        assert_eq!((var.start, var.end), (10, 11));
+    }
+
+    #[test]
+    fn test_normalize_mismatch() {
+        let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+        let var = Variant::from_hgvs("chrT:2G>T").unwrap();
+        assert!(matches!(
+            var.normalize(&mut reference, Normalization::Left),
+            Err(Error::RefMismatch { .. })
+        ));
+        let var = Variant::from_hgvs("chrT:2C>T").unwrap();
+        assert!(var.normalize(&mut reference, Normalization::Left).is_ok());
+    }
+
     #[test]
     fn test_parse_deletion() {
         let var_str = "1:12345_12346del";
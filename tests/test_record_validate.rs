@@ -1,8 +1,18 @@
 extern crate bam;
 extern crate varlink;
 
-use bam::BamReader;
-use varlink::{ValidateOptions, Variant, VariantValidate};
+use bam::{BamReader, Header, Record};
+use varlink::{Allele, Error, Reason, Reference, ValidateOptions, Variant, VariantValidate};
+
+/// Record from sam line, on contigs `chrT` of `tests/ref.fa` and `chrL`.
+fn sam_record(line: &str) -> Record {
+    let mut header = Header::new();
+    header.push_line("@SQ\tSN:chrT\tLN:60").unwrap();
+    header.push_line("@SQ\tSN:chrL\tLN:10000").unwrap();
+    let mut rec = Record::new();
+    rec.fill_from_sam(line, &header).unwrap();
+    rec
+}
 
 #[test]
 fn test_validate_insertion() {
@@ -21,7 +31,7 @@ fn test_validate_insertion() {
             break v;
         };
     };
-    assert!(rec.validate(&var, &options).unwrap());
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
     let rec = loop {
         let v = if let Some(r) = reader.next() {
             r
@@ -33,13 +43,15 @@ fn test_validate_insertion() {
             break v;
         };
     };
-    assert!(rec.validate(&var, &options).unwrap());
-    assert!(!rec
-        .validate(
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(
+        rec.validate(
             &Variant::from_hgvs("1:144852532_144852533insCCG").unwrap(),
             &options
         )
-        .unwrap());
+        .unwrap(),
+        Some(Allele::Other)
+    );
 }
 
 #[test]
@@ -59,7 +71,7 @@ fn test_validate_deletion() {
             break v;
         };
     };
-    assert!(rec.validate(&var, &options).unwrap());
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
     let rec = loop {
         let v = if let Some(r) = reader.next() {
             r
@@ -71,13 +83,16 @@ fn test_validate_deletion() {
             break v;
         };
     };
-    assert!(rec.validate(&var, &options).unwrap());
-    assert!(!rec
-        .validate(
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    // Deletion stops partway through longer one.
+    assert_eq!(
+        rec.reason(
             &Variant::from_hgvs("1:144852633_144852635del").unwrap(),
             &options
         )
-        .unwrap());
+        .unwrap(),
+        Reason::Partial
+    );
 }
 
 #[test]
@@ -97,10 +112,13 @@ fn test_validate_snv() {
             break v;
         };
     };
-    assert!(rec.validate(&var, &options).unwrap());
-    assert!(!rec
-        .validate(&Variant::from_hgvs("1:144852545C>A").unwrap(), &options)
-        .unwrap());
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    // Read shows a third base.
+    assert_eq!(
+        rec.validate(&Variant::from_hgvs("1:144852545C>A").unwrap(), &options)
+            .unwrap(),
+        Some(Allele::Other)
+    );
 }
 
 #[test]
@@ -123,12 +141,12 @@ fn test_validate_merge() {
     };
     // Ignore read with *merge* variant.
     options.set_merge(true);
-    assert!(!rec.validate(&var1, &options).unwrap());
-    assert!(!rec.validate(&var2, &options).unwrap());
+    assert_eq!(rec.validate(&var1, &options).unwrap(), Some(Allele::Other));
+    assert_eq!(rec.validate(&var2, &options).unwrap(), Some(Allele::Other));
     // Include read with *merge* variant.
     options.set_merge(false);
-    assert!(rec.validate(&var1, &options).unwrap());
-    assert!(rec.validate(&var2, &options).unwrap());
+    assert_eq!(rec.validate(&var1, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(rec.validate(&var2, &options).unwrap(), Some(Allele::Alt));
 }
 
 #[test]
@@ -148,6 +166,416 @@ fn test_validate_multiple_snp() {
             break v;
         };
     };
-    assert!(rec.validate(&var1, &options).unwrap());
-    assert!(rec.validate(&var2, &options).unwrap());
+    assert_eq!(rec.validate(&var1, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(rec.validate(&var2, &options).unwrap(), Some(Allele::Alt));
+}
+
+#[test]
+fn test_validate_min_base_qual() {
+    // MACH:453:FLOWCELL:1:2121:4255:24267, alt base quality 34 or 35 in each mate.
+    let var = Variant::from_hgvs("1:144852545C>T").unwrap();
+    let mut options = ValidateOptions::default();
+    let mut reader = BamReader::from_path("tests/test.1:144852532-144852632.bam", 0).unwrap();
+    let rec = loop {
+        let v = if let Some(r) = reader.next() {
+            r
+        } else {
+            panic!("Record not found.")
+        };
+        let v = v.unwrap();
+        if v.name() == b"MACH:453:FLOWCELL:1:2121:4255:24267" {
+            break v;
+        };
+    };
+    options.set_min_base_qual(30);
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    options.set_min_base_qual(36);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+    assert!(rec
+        .validate(&Variant::from_hgvs("1:144852545C>A").unwrap(), &options)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_validate_read_filters() {
+    let var = Variant::from_hgvs("1:144852545C>T").unwrap();
+    let mut options = ValidateOptions::default();
+    let mut reader = BamReader::from_path("tests/test.1:144852532-144852632.bam", 0).unwrap();
+    let mut rec = loop {
+        let v = if let Some(r) = reader.next() {
+            r
+        } else {
+            panic!("Record not found.")
+        };
+        let v = v.unwrap();
+        if v.name() == b"MACH:453:FLOWCELL:1:2121:4255:24267" {
+            break v;
+        };
+    };
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    // Duplicate excluded by default.
+    rec.flag_mut().set_duplicate(true);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+    options.set_exclude_flags(0);
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    options.set_include_flags(0x800);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+    options.set_include_flags(0);
+    options.set_min_mapq(rec.mapq() + 1);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+}
+
+#[test]
+fn test_validate_realign() {
+    let var = Variant::from_hgvs("1:144852532_144852533insCCC").unwrap();
+    let mut options = ValidateOptions::default();
+    options.set_realign(true);
+    let mut reader = BamReader::from_path("tests/test.1:144852532-144852632.bam", 0).unwrap();
+    let rec = loop {
+        let v = if let Some(r) = reader.next() {
+            r
+        } else {
+            panic!("Record not found.")
+        };
+        let v = v.unwrap();
+        if v.name() == b"MACH:453:FLOWCELL:1:1222:15483:21825" {
+            break v;
+        };
+    };
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+
+    // Insertion near read end, aligned as mismatches and soft clip.
+    let rec = sam_record(
+        "read\t0\tchrT\t1\t60\t14M12S\t*\t0\t0\tACGTAAAAAGCTGGCACACAGTTTGG\t*\tMD:Z:12C0A0",
+    );
+    let var = Variant::from_hgvs("chrT:12_13insGG").unwrap();
+    assert_eq!(
+        rec.validate(&var, &ValidateOptions::default()).unwrap(),
+        Some(Allele::Other)
+    );
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    let var = Variant::from_hgvs("chrT:12_13insTT").unwrap();
+    assert_ne!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+
+    // Insertion soft clipped, haplotypes taken from reference.
+    let rec =
+        sam_record("read\t0\tchrT\t1\t60\t12M14S\t*\t0\t0\tACGTAAAAAGCTGGCACACAGTTTGG\t*\tMD:Z:12");
+    let var = Variant::from_hgvs("chrT:12_13insGG").unwrap();
+    let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::TooShort);
+    assert_eq!(
+        rec.reason_with_reference(&var, &options, Some(&mut reference))
+            .unwrap(),
+        Reason::Supported
+    );
+    let var = Variant::from_hgvs("chrT:12_13insTT").unwrap();
+    assert_eq!(
+        rec.reason_with_reference(&var, &options, Some(&mut reference))
+            .unwrap(),
+        Reason::Reference
+    );
+}
+
+#[test]
+fn test_validate_soft_clip() {
+    let mut options = ValidateOptions::default();
+    options.set_soft_clip(true);
+    let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    let mut validate = |rec: &Record, var: &Variant| {
+        rec.reason_with_reference(var, &options, Some(&mut reference))
+            .unwrap()
+            .allele()
+    };
+
+    // Insertion at read end.
+    let var = Variant::from_hgvs("chrT:12_13insGG").unwrap();
+    let alt = sam_record("alt\t0\tchrT\t1\t60\t12M6S\t*\t0\t0\tACGTAAAAAGCTGGCACA\t*\tMD:Z:12");
+    let rf = sam_record("ref\t0\tchrT\t1\t60\t12M6S\t*\t0\t0\tACGTAAAAAGCTCACACA\t*\tMD:Z:12");
+    let short = sam_record("short\t0\tchrT\t1\t60\t12M1S\t*\t0\t0\tACGTAAAAAGCTA\t*\tMD:Z:12");
+    assert_eq!(
+        alt.validate(&var, &ValidateOptions::default()).unwrap(),
+        Some(Allele::Other)
+    );
+    assert_eq!(validate(&alt, &var), Some(Allele::Alt));
+    assert_eq!(validate(&rf, &var), Some(Allele::Ref));
+    // Clip matches neither.
+    assert!(validate(&short, &var).is_none());
+    // Clip matches both in repeat.
+    let var = Variant::from_hgvs("chrT:12_13insCA").unwrap();
+    assert!(validate(&rf, &var).is_none());
+
+    // Deletion at read start.
+    let var = Variant::from_hgvs("chrT:19_20del").unwrap();
+    let alt = sam_record("alt\t0\tchrT\t21\t60\t6S12M\t*\t0\t0\tCACACATTGGGCCCATAT\t*\tMD:Z:12");
+    let rf = sam_record("ref\t0\tchrT\t21\t60\t6S12M\t*\t0\t0\tCACAGTTTGGGCCCATAT\t*\tMD:Z:12");
+    assert!(alt
+        .validate(&var, &ValidateOptions::default())
+        .unwrap()
+        .is_none());
+    assert_eq!(validate(&alt, &var), Some(Allele::Alt));
+    assert_eq!(validate(&rf, &var), Some(Allele::Ref));
+    // Near start of clip.
+    let mut near = ValidateOptions::default();
+    near.set_soft_clip(true);
+    near.set_end_distance(5);
+    assert_eq!(
+        alt.reason_with_reference(&var, &near, Some(&mut reference))
+            .unwrap(),
+        Reason::NearEnd
+    );
+    near.set_end_distance(4);
+    assert_eq!(
+        alt.reason_with_reference(&var, &near, Some(&mut reference))
+            .unwrap(),
+        Reason::Supported
+    );
+    // Reference not given.
+    assert!(matches!(
+        rf.reason(&var, &options),
+        Err(Error::ReferenceRequired(_))
+    ));
+}
+
+#[test]
+fn test_validate_missing_md() {
+    let rec = sam_record("read\t0\tchrT\t1\t60\t12M\t*\t0\t0\tACGTAAAAAGCT\t*");
+    let var = Variant::from_hgvs("chrT:5A>G").unwrap();
+    assert!(matches!(
+        rec.validate(&var, &ValidateOptions::default()),
+        Err(Error::MissingMd(_))
+    ));
+    // Out of region.
+    let var = Variant::from_hgvs("chrT:30A>G").unwrap();
+    assert!(rec
+        .validate(&var, &ValidateOptions::default())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_validate_dup() {
+    let mut reader = BamReader::from_path("tests/test.1:144852532-144852632.bam", 0).unwrap();
+    let rec = loop {
+        let v = if let Some(r) = reader.next() {
+            r
+        } else {
+            panic!("Record not found.")
+        };
+        let v = v.unwrap();
+        if v.name() == b"MACH:453:FLOWCELL:1:1222:15483:21825" {
+            break v;
+        };
+    };
+    let options = ValidateOptions::default();
+    let var = Variant::from_hgvs("1:144852530_144852532dupCCC").unwrap();
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    // Duplicated sequence unknown without reference.
+    let var = Variant::from_hgvs("1:144852530_144852532dup").unwrap();
+    assert!(matches!(
+        rec.validate(&var, &options),
+        Err(Error::ReferenceRequired(_))
+    ));
+
+    // Duplicated sequence filled by reference, chrT:10-12 is GCT.
+    let mut reference = Reference::from_path("tests/ref.fa").unwrap();
+    let var = Variant::from_hgvs("chrT:10_12dup").unwrap();
+    let rec =
+        sam_record("read\t0\tchrT\t1\t60\t12M3I8M\t*\t0\t0\tACGTAAAAAGCTGCTCACACAGT\t*\tMD:Z:20");
+    assert_eq!(
+        rec.reason_with_reference(&var, &options, Some(&mut reference))
+            .unwrap(),
+        Reason::Supported
+    );
+}
+
+#[test]
+fn test_validate_inversion() {
+    let options = ValidateOptions::default();
+    let var = Variant::from_hgvs("chrT:g.19_24inv").unwrap();
+
+    // Aligned through inversion, GTTTGG inverted to CCAAAC.
+    let alt =
+        sam_record("alt\t0\tchrT\t13\t60\t18M\t*\t0\t0\tCACACACCAAACGCCCAT\t*\tMD:Z:6G0T0T0T0G0G6");
+    let rf = sam_record("ref\t0\tchrT\t13\t60\t18M\t*\t0\t0\tCACACAGTTTGGGCCCAT\t*\tMD:Z:18");
+    let short = sam_record("short\t0\tchrT\t13\t60\t9M\t*\t0\t0\tCACACAGTT\t*\tMD:Z:9");
+    assert_eq!(alt.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(rf.validate(&var, &options).unwrap(), Some(Allele::Ref));
+    assert!(short.validate(&var, &options).unwrap().is_none());
+
+    // Split alignment inside inversion on opposite strand.
+    let split = sam_record(
+        "split\t0\tchrT\t1\t60\t18M6S\t*\t0\t0\tACGTAAAAAGCTCACACACCAAAC\t*\tMD:Z:18\tSA:Z:chrT,19,-,18S6M,60,0;",
+    );
+    let same = sam_record(
+        "same\t0\tchrT\t1\t60\t18M6S\t*\t0\t0\tACGTAAAAAGCTCACACACCAAAC\t*\tMD:Z:18\tSA:Z:chrT,19,+,18S6M,60,0;",
+    );
+    assert_eq!(split.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert!(same.validate(&var, &options).unwrap().is_none());
+}
+
+#[test]
+fn test_validate_large_deletion() {
+    let seq = "A".repeat(150);
+    let mut options = ValidateOptions::default();
+    let var = Variant::from_hgvs("chrL:g.1001_3000del").unwrap();
+
+    // Split read, primary before and supplementary after deletion.
+    let split = sam_record(&format!(
+        "split\t0\tchrL\t901\t60\t100M50S\t*\t0\t0\t{}\t*\tSA:Z:chrL,3001,+,100S50M,60,0;",
+        seq
+    ));
+    let strand = sam_record(&format!(
+        "strand\t0\tchrL\t901\t60\t100M50S\t*\t0\t0\t{}\t*\tSA:Z:chrL,3001,-,100S50M,60,0;",
+        seq
+    ));
+    let supplementary = sam_record(&format!(
+        "split\t2048\tchrL\t3001\t60\t100H50M\t*\t0\t0\t{}\t*\tSA:Z:chrL,901,+,100M50S,60,0;",
+        &seq[..50]
+    ));
+    assert_eq!(split.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert!(strand.validate(&var, &options).unwrap().is_none());
+    assert!(supplementary.validate(&var, &options).unwrap().is_none());
+
+    // Discordant pair flanking deletion.
+    let left = sam_record(&format!(
+        "pair\t33\tchrL\t801\t60\t150M\t=\t3101\t2450\t{}\t*",
+        seq
+    ));
+    let right = sam_record(&format!(
+        "pair\t17\tchrL\t3101\t60\t150M\t=\t801\t-2450\t{}\t*",
+        seq
+    ));
+    let proper = sam_record(&format!(
+        "proper\t33\tchrL\t701\t60\t150M\t=\t951\t400\t{}\t*",
+        seq
+    ));
+    assert_eq!(left.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(right.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert!(proper.validate(&var, &options).unwrap().is_none());
+    // Deletion shorter than read is left to aligned reads.
+    let short = Variant::from_hgvs("chrL:g.1001_1100del").unwrap();
+    let pair = sam_record(&format!(
+        "pair\t33\tchrL\t801\t60\t150M\t=\t1701\t1050\t{}\t*",
+        seq
+    ));
+    assert_eq!(pair.reason(&short, &options).unwrap(), Reason::NotCovered);
+    options.set_max_insert(0);
+    assert!(left.validate(&var, &options).unwrap().is_none());
+}
+
+#[test]
+fn test_validate_long_read() {
+    let mut options = ValidateOptions::default();
+    options.set_long_read(true);
+    let rf = "ACGTAAAAAGCTCACACAGTTTGGGCCCATATATGCAGTC";
+
+    // Insertion of GGGG read as GGG.
+    let var = Variant::from_hgvs("chrT:12_13insGGGG").unwrap();
+    let alt = sam_record(&format!(
+        "alt\t0\tchrT\t1\t60\t12M3I28M\t*\t0\t0\t{}GGG{}\t*\tMD:Z:40",
+        &rf[..12],
+        &rf[12..]
+    ));
+    let reference = sam_record(&format!(
+        "ref\t0\tchrT\t1\t60\t40M\t*\t0\t0\t{}\t*\tMD:Z:40",
+        rf
+    ));
+    assert!(alt
+        .validate(&var, &ValidateOptions::default())
+        .unwrap()
+        .is_none());
+    assert_eq!(alt.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(
+        reference.validate(&var, &options).unwrap(),
+        Some(Allele::Ref)
+    );
+
+    // Substitution next to homopolymer with an extra A.
+    let var = Variant::from_hgvs("chrT:10G>T").unwrap();
+    let alt = sam_record(&format!(
+        "alt\t0\tchrT\t1\t60\t9M1I31M\t*\t0\t0\t{}AT{}\t*\tMD:Z:9G30",
+        &rf[..9],
+        &rf[10..]
+    ));
+    assert_eq!(alt.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(
+        reference.validate(&var, &options).unwrap(),
+        Some(Allele::Ref)
+    );
+
+    // Homopolymer length change, run at variant compared as is.
+    let var = Variant::from_hgvs("chrT:9del").unwrap();
+    let alt = sam_record(&format!(
+        "alt\t0\tchrT\t1\t60\t8M1D31M\t*\t0\t0\t{}{}\t*\tMD:Z:8^A31",
+        &rf[..8],
+        &rf[9..]
+    ));
+    assert_eq!(alt.validate(&var, &options).unwrap(), Some(Allele::Alt));
+    assert_eq!(
+        reference.validate(&var, &options).unwrap(),
+        Some(Allele::Ref)
+    );
+}
+
+#[test]
+fn test_validate_reason() {
+    let mut options = ValidateOptions::default();
+    let var = Variant::from_hgvs("chrT:13C>T").unwrap();
+    let cases = [
+        ("0\t20M\tACGTAAAAAGCTCACACAGT\tMD:Z:20", Reason::Reference),
+        ("0\t20M\tACGTAAAAAGCTTACACAGT\tMD:Z:12C7", Reason::Supported),
+        ("0\t20M\tACGTAAAAAGCTGACACAGT\tMD:Z:12C7", Reason::Mismatch),
+        ("0\t12M\tACGTAAAAAGCT\tMD:Z:12", Reason::NotCovered),
+        ("4\t20M\tACGTAAAAAGCTCACACAGT\tMD:Z:20", Reason::Unmapped),
+        ("1024\t20M\tACGTAAAAAGCTCACACAGT\tMD:Z:20", Reason::Filtered),
+    ];
+    for (fields, reason) in cases.iter() {
+        let fields: Vec<&str> = fields.split('\t').collect();
+        let rec = sam_record(&format!(
+            "read\t{}\tchrT\t1\t60\t{}\t*\t0\t0\t{}\t*\t{}",
+            fields[0], fields[1], fields[2], fields[3]
+        ));
+        assert_eq!(rec.reason(&var, &options).unwrap(), *reason);
+        assert_eq!(rec.validate(&var, &options).unwrap(), reason.allele());
+    }
+
+    // Neighbor mismatch merged into variant.
+    let rec =
+        sam_record("read\t0\tchrT\t1\t60\t20M\t*\t0\t0\tACGTAAAAAGCTTGCACAGT\t*\tMD:Z:12C0A6");
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::Supported);
+    options.set_merge(true);
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::MergedNeighbor);
+
+    let var = Variant::from_hgvs("chrT:12_13insGG").unwrap();
+    let rec =
+        sam_record("read\t0\tchrT\t1\t60\t12M2I8M\t*\t0\t0\tACGTAAAAAGCTTTCACACAGT\t*\tMD:Z:20");
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::WrongInsertion);
+    assert_eq!(rec.validate(&var, &options).unwrap(), Some(Allele::Other));
+    // Read stops partway through deletion, not evidence of other allele.
+    let var = Variant::from_hgvs("chrT:13_16del").unwrap();
+    let rec = sam_record("read\t0\tchrT\t1\t60\t12M2D3M\t*\t0\t0\tACGTAAAAAGCTCAG\t*\tMD:Z:12^CA3");
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::Partial);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+
+    // Read ends inside deletion with a mismatch, not evidence of other allele.
+    let rec = sam_record("read\t0\tchrT\t1\t60\t13M\t*\t0\t0\tACGTAAAAAGCTT\t*\tMD:Z:12C0");
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::Partial);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+}
+
+#[test]
+fn test_validate_end_distance() {
+    let rec =
+        sam_record("read\t0\tchrT\t3\t60\t2S18M\t*\t0\t0\tTTGTAAAAAGCTTACACAGT\t*\tMD:Z:10C7");
+    let var = Variant::from_hgvs("chrT:13C>T").unwrap();
+    let mut options = ValidateOptions::default();
+    options.set_end_distance(8);
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::NearEnd);
+    assert!(rec.validate(&var, &options).unwrap().is_none());
+    options.set_end_distance(7);
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::Supported);
+    // Next to soft clip.
+    let var = Variant::from_hgvs("chrT:4T>A").unwrap();
+    assert_eq!(rec.reason(&var, &options).unwrap(), Reason::NearEnd);
 }